@@ -1,33 +1,72 @@
 use crate::error::ContractError;
-use crate::msg::{ExecuteMsg, InstantiateMsg, QueryMsg};
+use crate::msg::{
+    ExecuteMsg, InstantiateMsg, QueryMsg, ReceiveMsg, StakingHookMsg,
+};
 use crate::response::ConfigResponse;
 use crate::state::{
-    bet_info_key, bet_info_storage, BetInfo, MyGameResponse,
-    PendingRewardResponse, ACCUMULATED_FEE, CONFIG, IS_HAULTED, LIVE_ROUND,
-    NEXT_ROUND, NEXT_ROUND_ID, ROUNDS,
+    bet_info_key, bet_info_storage, player_stats_storage, BetInfo, HouseEpoch,
+    HouseRequest, MyGameResponse, PendingRewardResponse, RoundBetsResponse,
+    StakerInfo, ACCUMULATED_FEE, BUCKET_POOLS, CLAIMABLE, CLAIM_OPERATORS,
+    CONFIG, FEE_DISTRIBUTIONS, FEE_RECIPIENTS, FEE_TIERS, GLOBAL_STATS,
+    HOUSE_EPOCHS, HOUSE_POOL, HOUSE_REQUESTS, HOUSE_SHARES, IS_HAULTED,
+    KNOWN_STAKE_TOKENS, LIVE_ROUND, NEXT_FEE_DISTRIBUTION_ID, NEXT_ROUND,
+    NEXT_ROUND_ID, NEXT_STRIKE_MARKET_ID, PLAYERS, PLAYER_VOLUME,
+    POSITION_PAYOUTS, REFERRALS, REFERRAL_CLAIMABLE, REFERRAL_EARNINGS,
+    REFERRERS, ROUNDS, ROUND_BUCKETS, ROUND_POOLS, ROUND_POOL_FEE_RATES,
+    ROUND_STATS, SETTLED_BETS, STAKERS, STAKE_TOKENS, STAKING_STATE,
+    STRIKE_CREATORS, STRIKE_MARKETS, TOKEN_ACCUMULATED_FEE,
 };
 use crate::{Config, Direction, PartialConfig};
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    to_binary, Addr, Binary, Coin, CosmosMsg, Decimal, Deps, DepsMut, Env,
-    Event, MessageInfo, Order, QueryRequest, Response, StdError, StdResult,
-    Uint128, WasmMsg, WasmQuery,
+    from_binary, to_binary, Addr, Api, Binary, Coin, CosmosMsg, Decimal, Deps,
+    DepsMut, Env, Event, MessageInfo, Order, QueryRequest, Response, StdError,
+    StdResult, Storage, Timestamp, Uint128, Uint256, WasmMsg, WasmQuery,
 };
-use cw20::Cw20ExecuteMsg;
+use cw20::{BalanceResponse, Cw20ExecuteMsg, Cw20QueryMsg, Cw20ReceiveMsg};
 use cw_storage_plus::Bound;
 use hopers_bet::fast_oracle::msg::QueryMsg as FastOracleQueryMsg;
+use hopers_bet::position_nft::{
+    msg::Cw721ReceiveMsg, msg::ExecuteMsg as PositionNftExecuteMsg,
+    msg::QueryMsg as PositionNftQueryMsg, msg::TokensResponse,
+    PositionMetadata,
+};
 use hopers_bet::price_prediction::response::{
-    MyCurrentPositionResponse, StatusResponse,
+    ClaimOperatorsResponse, FeeDistributionsResponse, FeeRecipientsResponse,
+    FeeTiersResponse, FinishedRoundsResponse, HousePoolResponse,
+    HouseSharesResponse, LeaderboardEntry, LeaderboardResponse,
+    MyCurrentPositionResponse, OddsResponse, PlayerFeeTierResponse,
+    ReferralEarningsResponse, ReferredPlayersResponse, RoundAtTimeResponse,
+    RoundBucketsResponse, RoundPoolsResponse, SimulateBetResponse,
+    StakeTokensResponse, StakerResponse, StatusResponse, StrikeMarketsResponse,
 };
 use hopers_bet::price_prediction::{
-    FinishedRound, LiveRound, MigrateMsg, NextRound, WalletInfo, FEE_PRECISION,
+    AssetAmount, AssetInfo, FeeDistribution, FeeModel, FeeStaking, FeeTier,
+    FeeTierRequirement, FinishedRound, GlobalStats, HousePool,
+    LeaderboardOrder, LiveRound, MigrateMsg, NextRound, OrderBy, PlayerStats,
+    RoundPool, StakeToken, StrikeMarket, TiePolicy, WalletInfo, BPS_PRECISION,
+    FEE_PRECISION, STRIKE_MARKET_ID_OFFSET,
 };
 
 // Query limits
 const DEFAULT_QUERY_LIMIT: u32 = 10;
 const MAX_QUERY_LIMIT: u32 = 30;
 
+// Bets settled per claim
+const DEFAULT_CLAIM_LIMIT: u32 = 30;
+const MAX_CLAIM_LIMIT: u32 = 100;
+// Finished bets a new bet resolves along the way
+const BET_RESOLVE_LIMIT: usize = 5;
+
+// Trailing volume of fee tiers: 30 daily epochs
+const VOLUME_EPOCH_SECONDS: u64 = 86_400;
+const VOLUME_EPOCHS: u64 = 30;
+// cw721 tokens counted for fee tiers
+const MAX_CW721_COUNT: u32 = 100;
+// Strike markets settled later than this after expiry are refunded
+const STRIKE_SETTLE_WINDOW_SECONDS: u64 = 60;
+
 const CONTRACT_NAME: &str = "deliverdao:price_prediction";
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
@@ -39,9 +78,7 @@ pub fn instantiate(
     msg: InstantiateMsg,
 ) -> Result<Response, ContractError> {
     cw2::set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
-    /* Validate addresses */
-    deps.api
-        .addr_validate(msg.config.fast_oracle_addr.as_ref())?;
+    validate_config(deps.as_ref(), &msg.config)?;
 
     CONFIG.save(deps.storage, &msg.config)?;
     NEXT_ROUND_ID.save(deps.storage, &0u128)?;
@@ -76,18 +113,143 @@ pub fn execute(
         ExecuteMsg::UpdateConfig { config } => {
             execute_update_config(deps, info, env, config)
         }
-        ExecuteMsg::BetBear { round_id, amount } => {
-            execute_bet(deps, info, env, round_id, Direction::Bear, amount)
-        }
-        ExecuteMsg::BetBull { round_id, amount } => {
-            execute_bet(deps, info, env, round_id, Direction::Bull, amount)
+        ExecuteMsg::BetBear {
+            round_id,
+            amount,
+            token,
+            referrer,
+        } => execute_bet(
+            deps,
+            env,
+            BetParams {
+                funding: BetFunding::Wallet(info),
+                round_id,
+                side: BetSide::Direction(Direction::Bear),
+                gross: amount,
+                token,
+                referrer,
+            },
+        ),
+        ExecuteMsg::BetBull {
+            round_id,
+            amount,
+            token,
+            referrer,
+        } => execute_bet(
+            deps,
+            env,
+            BetParams {
+                funding: BetFunding::Wallet(info),
+                round_id,
+                side: BetSide::Direction(Direction::Bull),
+                gross: amount,
+                token,
+                referrer,
+            },
+        ),
+        ExecuteMsg::BetBucket {
+            round_id,
+            bucket,
+            amount,
+        } => execute_bet(
+            deps,
+            env,
+            BetParams {
+                funding: BetFunding::Wallet(info),
+                round_id,
+                side: BetSide::Bucket(bucket),
+                gross: amount,
+                token: None,
+                referrer: None,
+            },
+        ),
+        ExecuteMsg::Receive(msg) => execute_receive(deps, env, info, msg),
+        ExecuteMsg::WithdrawBet { round_id, amount } => {
+            execute_withdraw_bet(deps, info, env, round_id, amount)
         }
         ExecuteMsg::CloseRound {} => execute_close_round(deps, env),
-        ExecuteMsg::CollectWinnings {} => execute_collect_winnings(deps, info),
+        ExecuteMsg::CollectWinnings {
+            round_ids,
+            limit,
+            player,
+            recipient,
+        } => execute_collect_winnings(
+            deps, info, round_ids, limit, player, recipient,
+        ),
+        ExecuteMsg::ClaimReferralRewards {} => {
+            execute_claim_referral_rewards(deps, info)
+        }
+        ExecuteMsg::Unstake { amount } => {
+            execute_unstake(deps, env, info, amount)
+        }
+        ExecuteMsg::ClaimStakingRewards {} => {
+            execute_claim_staking_rewards(deps, info)
+        }
+        ExecuteMsg::DepositHouse {} => match info.funds.as_slice() {
+            [coin] => execute_deposit_house(
+                deps,
+                AssetInfo::NativeToken {
+                    denom: coin.denom.clone(),
+                },
+                info.sender.clone(),
+                coin.amount,
+            ),
+            _ => Err(ContractError::Std(StdError::generic_err(
+                "Send exactly one coin of the market's stake",
+            ))),
+        },
+        ExecuteMsg::WithdrawHouse { shares } => {
+            execute_withdraw_house(deps, info, shares)
+        }
+        ExecuteMsg::ClaimHouse {} => execute_claim_house(deps, info),
+        ExecuteMsg::SettleRound { round_id, limit } => {
+            execute_settle_round(deps, round_id, limit)
+        }
+        ExecuteMsg::TransferBet {
+            round_id,
+            recipient,
+        } => execute_transfer_bet(deps, info, round_id, recipient),
+        ExecuteMsg::ReceiveNft(msg) => execute_receive_nft(deps, info, msg),
+        ExecuteMsg::CreateStrikeMarket {
+            strike_price,
+            cutoff_time,
+            expiry_time,
+        } => execute_create_strike_market(
+            deps,
+            env,
+            info,
+            strike_price,
+            cutoff_time,
+            expiry_time,
+        ),
+        ExecuteMsg::SettleStrikeMarket { market_id } => {
+            execute_settle_strike_market(deps, env, info, market_id)
+        }
+        ExecuteMsg::SetStrikeCreator { creator, allowed } => {
+            execute_set_strike_creator(deps, env, info, creator, allowed)
+        }
+        ExecuteMsg::AllowClaimOperator { operator } => {
+            execute_allow_claim_operator(deps, info, operator)
+        }
+        ExecuteMsg::RevokeClaimOperator { operator } => {
+            execute_revoke_claim_operator(deps, info, operator)
+        }
         ExecuteMsg::Hault {} => execute_update_hault(deps, info, env, true),
         ExecuteMsg::Resume {} => execute_update_hault(deps, info, env, false),
-        ExecuteMsg::DistributeFund { dev_wallet_list } => {
-            execute_distribute_fund(deps, env, info, dev_wallet_list)
+        ExecuteMsg::DistributeFund {} => {
+            execute_distribute_fund(deps, env, info)
+        }
+        ExecuteMsg::SetFeeRecipients { recipients } => {
+            execute_set_fee_recipients(deps, info, env, recipients)
+        }
+        ExecuteMsg::SetStakeToken { token } => {
+            execute_set_stake_token(deps, info, env, token)
+        }
+        ExecuteMsg::RemoveStakeToken { asset } => {
+            execute_remove_stake_token(deps, info, env, asset)
+        }
+        ExecuteMsg::SetFeeTiers { tiers } => {
+            execute_set_fee_tiers(deps, info, env, tiers)
         }
     }
 }
@@ -96,231 +258,2376 @@ fn execute_distribute_fund(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
-    dev_wallet_list: Vec<WalletInfo>,
 ) -> Result<Response, ContractError> {
-    assert_is_admin(deps.as_ref(), info, env)?;
+    assert_is_admin(deps.as_ref(), info, env.clone())?;
 
     let config = CONFIG.load(deps.storage)?;
-    let collected_fee = ACCUMULATED_FEE.load(deps.storage)?;
+    let recipients = FEE_RECIPIENTS.may_load(deps.storage)?.unwrap_or_default();
+    if recipients.is_empty() {
+        return Err(ContractError::Std(StdError::generic_err(
+            "Set the fee recipients first",
+        )));
+    }
 
-    let mut total_ratio = Decimal::zero();
     let mut messages: Vec<CosmosMsg> = Vec::new();
-    for dev_wallet in dev_wallet_list.clone() {
-        total_ratio = total_ratio + dev_wallet.ratio;
-    }
+    let mut amounts: Vec<AssetAmount> = vec![];
 
-    if total_ratio != Decimal::one() {
-        return Err(ContractError::WrongRatio {});
-    }
+    let collected_fee = Uint128::new(ACCUMULATED_FEE.load(deps.storage)?);
+    let staking_amount = distribute_staking_share(
+        deps.storage,
+        &config,
+        collected_fee,
+        &mut messages,
+    )?;
+    let house_amount =
+        distribute_house_share(deps.storage, &config, collected_fee)?;
+    let distributed = distribute_fee(
+        &recipients,
+        &config.stake_asset(),
+        collected_fee - staking_amount - house_amount,
+        &mut messages,
+    )?;
+    ACCUMULATED_FEE.save(
+        deps.storage,
+        &(collected_fee - staking_amount - house_amount - distributed).u128(),
+    )?;
+    update_global_stats(deps.storage, |stats| {
+        stats.total_fees_distributed +=
+            staking_amount + house_amount + distributed
+    })?;
+    add_payout(&mut amounts, config.stake_asset(), distributed);
 
-    for dev_wallet in dev_wallet_list {
-        let token_transfer_msg = get_cw20_transfer_msg(
-            &config.token_addr,
-            &dev_wallet.address,
-            Uint128::new(collected_fee) * dev_wallet.ratio,
+    /* Removed tokens' fees are paid out too */
+    for (key, collected_fee) in TOKEN_ACCUMULATED_FEE
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?
+    {
+        let stake_token = load_known_stake_token(deps.storage, &key)?;
+        let collected_fee = Uint128::new(collected_fee);
+        let distributed = distribute_fee(
+            &recipients,
+            &stake_token.asset,
+            collected_fee,
+            &mut messages,
+        )?;
+        TOKEN_ACCUMULATED_FEE.save(
+            deps.storage,
+            &key,
+            &(collected_fee - distributed).u128(),
         )?;
-        messages.push(token_transfer_msg)
+        add_payout(&mut amounts, stake_token.asset, distributed);
+    }
+
+    if amounts.is_empty() && staking_amount.is_zero() && house_amount.is_zero()
+    {
+        return Err(ContractError::Std(StdError::generic_err(
+            "Nothing to distribute",
+        )));
     }
 
+    let id = NEXT_FEE_DISTRIBUTION_ID
+        .may_load(deps.storage)?
+        .unwrap_or_default();
+    NEXT_FEE_DISTRIBUTION_ID.save(deps.storage, &(id + 1))?;
+    FEE_DISTRIBUTIONS.save(
+        deps.storage,
+        id,
+        &FeeDistribution {
+            id,
+            time: env.block.time,
+            amounts,
+            recipients,
+            staking_amount,
+            house_amount,
+        },
+    )?;
+
     Ok(Response::new()
         .add_attribute("action", "distribute_reward")
+        .add_attribute("distribution", id.to_string())
         .add_messages(messages))
 }
 
-fn execute_collect_winnings(
+/**
+ * Takes the stakers' share off the market's own fees; nothing is taken
+ * while nobody stakes internally
+ */
+fn distribute_staking_share(
+    storage: &mut dyn Storage,
+    config: &Config,
+    fee: Uint128,
+    messages: &mut Vec<CosmosMsg>,
+) -> StdResult<Uint128> {
+    let amount = config
+        .staking_share
+        .checked_multiply_ratio(fee, FEE_PRECISION * 100)
+        .map_err(|e| StdError::generic_err(e.to_string()))?;
+    if amount.is_zero() {
+        return Ok(amount);
+    }
+
+    match &config.fee_staking {
+        FeeStaking::Disabled => Ok(Uint128::zero()),
+        FeeStaking::Contract { contract_addr } => {
+            messages.push(get_staking_hook_msg(
+                &config.stake_asset(),
+                contract_addr,
+                amount,
+            )?);
+            Ok(amount)
+        }
+        FeeStaking::Internal { .. } => {
+            let mut state =
+                STAKING_STATE.may_load(storage)?.unwrap_or_default();
+            if state.total_staked.is_zero() {
+                return Ok(Uint128::zero());
+            }
+            let total = amount + state.undistributed;
+            let reward_per_share =
+                Decimal::from_ratio(total, state.total_staked);
+            state.reward_per_share = state.reward_per_share + reward_per_share;
+            state.undistributed = total - state.total_staked * reward_per_share;
+            STAKING_STATE.save(storage, &state)?;
+            Ok(amount)
+        }
+    }
+}
+
+/**
+ * Adds the house pool's share of the market's own fees to its liquidity;
+ * nothing is taken while the pool has no shares
+ */
+fn distribute_house_share(
+    storage: &mut dyn Storage,
+    config: &Config,
+    fee: Uint128,
+) -> StdResult<Uint128> {
+    let mut pool = HOUSE_POOL.may_load(storage)?.unwrap_or_default();
+    if pool.total_shares.is_zero() {
+        return Ok(Uint128::zero());
+    }
+
+    let amount = config
+        .house_fee_share
+        .checked_multiply_ratio(fee, FEE_PRECISION * 100)
+        .map_err(|e| StdError::generic_err(e.to_string()))?;
+    pool.liquidity += amount;
+    HOUSE_POOL.save(storage, &pool)?;
+    Ok(amount)
+}
+
+/**
+ * Splits a fee between the recipients; returns the amount paid out, the
+ * rounding dust is left over
+ */
+fn distribute_fee(
+    recipients: &[WalletInfo],
+    asset: &AssetInfo,
+    fee: Uint128,
+    messages: &mut Vec<CosmosMsg>,
+) -> StdResult<Uint128> {
+    let mut distributed = Uint128::zero();
+    for recipient in recipients.iter() {
+        let amount = fee * recipient.ratio;
+        if amount.is_zero() {
+            continue;
+        }
+        messages.push(get_asset_transfer_msg(
+            asset,
+            &recipient.address,
+            amount,
+        )?);
+        distributed += amount;
+    }
+    Ok(distributed)
+}
+
+fn execute_stake(
     deps: DepsMut,
-    info: MessageInfo,
+    env: Env,
+    token: AssetInfo,
+    staker: Addr,
+    amount: Uint128,
 ) -> Result<Response, ContractError> {
     let config = CONFIG.load(deps.storage)?;
-    let mut winnings = Uint128::zero();
-    let resp = Response::new();
+    let staking_token = match config.fee_staking {
+        FeeStaking::Internal { token_addr } => Some(AssetInfo::Token {
+            contract_addr: token_addr,
+        }),
+        _ => None,
+    };
+    if staking_token != Some(token) {
+        return Err(ContractError::Std(StdError::generic_err(
+            "This token can't be staked",
+        )));
+    }
+
+    /* So nobody can stake just for a distribution and leave right after */
+    let mut staker_info = load_staker(deps.storage, &staker)?;
+    staker_info.amount += amount;
+    staker_info.locked_until = env
+        .block
+        .time
+        .plus_seconds(config.staking_lock_seconds.u128() as u64);
+    STAKERS.save(deps.storage, &staker, &staker_info)?;
 
-    // let no_duplicate_rounds: HashSet<u128> =
-    //     HashSet::from_iter(rounds.iter().cloned());
+    let mut state = STAKING_STATE.may_load(deps.storage)?.unwrap_or_default();
+    state.total_staked += amount;
+    STAKING_STATE.save(deps.storage, &state)?;
 
-    let my_game_list =
-        query_my_games_without_limit(deps.as_ref(), info.sender.clone())?;
+    Ok(Response::new().add_event(
+        Event::new("hopers_bet")
+            .add_attribute("action", "stake")
+            .add_attribute("staker", staker)
+            .add_attribute("amount", amount)
+            .add_attribute(
+                "locked_until",
+                staker_info.locked_until.seconds().to_string(),
+            ),
+    ))
+}
 
-    for game in my_game_list.my_game_list {
-        let round_id = game.round_id;
-        let round = ROUNDS.load(deps.storage, round_id.u128())?;
+fn execute_unstake(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let token_addr = match config.fee_staking {
+        FeeStaking::Internal { token_addr } => token_addr,
+        _ => {
+            return Err(ContractError::Std(StdError::generic_err(
+                "Internal staking is disabled",
+            )));
+        }
+    };
 
-        let pool_shares = round.bear_amount + round.bull_amount;
-        let bet_info_key = bet_info_key(round_id.u128(), &info.sender);
+    let mut staker_info = load_staker(deps.storage, &info.sender)?;
+    if amount.is_zero() || amount > staker_info.amount {
+        return Err(ContractError::Std(StdError::generic_err(format!(
+            "Can unstake between 1 and {}",
+            staker_info.amount
+        ))));
+    }
+    if env.block.time < staker_info.locked_until {
+        return Err(ContractError::Std(StdError::generic_err(format!(
+            "Stake is locked for {} more second(s)",
+            staker_info.locked_until.seconds() - env.block.time.seconds()
+        ))));
+    }
+    staker_info.amount -= amount;
+    STAKERS.save(deps.storage, &info.sender, &staker_info)?;
 
-        bet_info_storage().remove(deps.storage, bet_info_key.clone())?;
+    let mut state = STAKING_STATE.load(deps.storage)?;
+    state.total_staked -= amount;
+    STAKING_STATE.save(deps.storage, &state)?;
 
-        if round.bear_amount == Uint128::zero()
-            || round.bull_amount == Uint128::zero()
-        {
-            winnings += game.amount;
-        } else {
-            let round_winnings = match round.winner {
-                Some(Direction::Bull) => {
-                    /* Only claimable once */
-                    match game.direction {
-                        Direction::Bull => {
-                            let won_shares = game.amount;
-                            pool_shares
-                                .multiply_ratio(won_shares, round.bull_amount)
-                        }
-                        Direction::Bear => Uint128::zero(),
-                    }
-                }
-                Some(Direction::Bear) => {
-                    /* Only claimable once */
-                    match game.direction {
-                        Direction::Bull => Uint128::zero(),
-                        Direction::Bear => {
-                            let won_shares = game.amount;
-                            pool_shares
-                                .multiply_ratio(won_shares, round.bear_amount)
-                        }
-                    }
-                }
-                None => {
-                    /* Only claimable once */
-                    game.amount
-                }
-            };
+    Ok(Response::new()
+        .add_message(get_cw20_transfer_msg(&token_addr, &info.sender, amount)?)
+        .add_event(
+            Event::new("hopers_bet")
+                .add_attribute("action", "unstake")
+                .add_attribute("staker", info.sender)
+                .add_attribute("amount", amount),
+        ))
+}
 
-            /* Count it up */
-            winnings += round_winnings;
-        }
-    }
+fn execute_claim_staking_rewards(
+    deps: DepsMut,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
 
-    if winnings == Uint128::zero() {
+    let mut staker_info = load_staker(deps.storage, &info.sender)?;
+    let reward = staker_info.pending_reward;
+    if reward.is_zero() {
         return Err(ContractError::Std(StdError::generic_err(
             "Nothing to claim",
         )));
     }
+    staker_info.pending_reward = Uint128::zero();
+    STAKERS.save(deps.storage, &info.sender, &staker_info)?;
+    record_paid_out(deps.storage, &config, &config.stake_asset(), reward)?;
 
-    let msg_send_winnings: CosmosMsg;
+    Ok(Response::new()
+        .add_message(get_asset_transfer_msg(
+            &config.stake_asset(),
+            &info.sender,
+            reward,
+        )?)
+        .add_event(
+            Event::new("hopers_bet")
+                .add_attribute("action", "claim-staking-rewards")
+                .add_attribute("staker", info.sender)
+                .add_attribute("amount", reward),
+        ))
+}
 
-    msg_send_winnings =
-        get_cw20_transfer_msg(&config.token_addr, &info.sender, winnings)?;
+fn execute_deposit_house(
+    deps: DepsMut,
+    asset: AssetInfo,
+    depositor: Addr,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if asset != config.stake_asset() {
+        return Err(ContractError::Std(StdError::generic_err(
+            "The house pool only takes the market's stake",
+        )));
+    }
 
-    Ok(resp
-        .add_message(msg_send_winnings)
-        .add_attribute("action", "collect-winnings")
-        .add_attribute("amount", winnings))
+    let mut pool = HOUSE_POOL.may_load(deps.storage)?.unwrap_or_default();
+    /* At today's NAV; the shares are bought at the epoch's */
+    let shares = if pool.total_shares.is_zero() {
+        amount
+    } else if pool.nav().is_zero() {
+        return Err(ContractError::Std(StdError::generic_err(
+            "The house pool has nothing left to back its shares",
+        )));
+    } else {
+        amount.multiply_ratio(pool.total_shares, pool.nav())
+    };
+    if shares.is_zero() {
+        return Err(ContractError::Std(StdError::generic_err(
+            "Deposit too small to get a share",
+        )));
+    }
+
+    let mut request = apply_house_request(deps.storage, &pool, &depositor)?;
+    request.deposit += amount;
+    pool.pending_deposits += amount;
+    HOUSE_REQUESTS.save(deps.storage, &depositor, &request)?;
+    HOUSE_POOL.save(deps.storage, &pool)?;
+
+    Ok(Response::new().add_event(
+        Event::new("hopers_bet")
+            .add_attribute("action", "deposit-house")
+            .add_attribute("depositor", depositor)
+            .add_attribute("amount", amount)
+            .add_attribute("epoch", pool.epoch.to_string()),
+    ))
 }
 
-fn execute_bet(
+fn execute_withdraw_house(
     deps: DepsMut,
     info: MessageInfo,
-    env: Env,
-    round_id: Uint128,
-    dir: Direction,
-    gross: Uint128,
+    shares: Uint128,
 ) -> Result<Response, ContractError> {
-    assert_not_haulted(deps.as_ref())?;
-
-    let mut bet_round = assert_is_current_round(deps.as_ref(), round_id)?;
-    let mut resp = Response::new();
-    let config = CONFIG.load(deps.storage)?;
+    let mut pool = HOUSE_POOL.may_load(deps.storage)?.unwrap_or_default();
+    let mut request = apply_house_request(deps.storage, &pool, &info.sender)?;
 
-    if env.block.time > bet_round.open_time {
+    let owned = HOUSE_SHARES
+        .may_load(deps.storage, &info.sender)?
+        .unwrap_or_default();
+    if shares.is_zero() || shares > owned {
         return Err(ContractError::Std(StdError::generic_err(format!(
-            "Round {} stopped accepting bids {} second(s) ago; the next round has not yet begun", round_id,
-                (env.block.time.seconds() - bet_round.open_time.seconds())
+            "Can withdraw between 1 and {} shares",
+            owned
         ))));
     }
 
-    let burn_fee = compute_burn_fee(deps.as_ref(), gross)?;
+    if shares == owned {
+        HOUSE_SHARES.remove(deps.storage, &info.sender);
+    } else {
+        HOUSE_SHARES.save(deps.storage, &info.sender, &(owned - shares))?;
+    }
+    request.shares += shares;
+    pool.pending_shares += shares;
+    HOUSE_REQUESTS.save(deps.storage, &info.sender, &request)?;
+    HOUSE_POOL.save(deps.storage, &pool)?;
 
-    if burn_fee > Uint128::zero() {
-        let msg_burn_fee =
-            get_cw20_burn_from_msg(&config.token_addr, &info.sender, burn_fee)?;
-        resp = resp.add_message(msg_burn_fee);
+    Ok(Response::new().add_event(
+        Event::new("hopers_bet")
+            .add_attribute("action", "withdraw-house")
+            .add_attribute("depositor", info.sender)
+            .add_attribute("shares", shares)
+            .add_attribute("epoch", pool.epoch.to_string()),
+    ))
+}
+
+fn execute_claim_house(
+    deps: DepsMut,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let mut pool = HOUSE_POOL.may_load(deps.storage)?.unwrap_or_default();
+    let mut request = apply_house_request(deps.storage, &pool, &info.sender)?;
+    let amount = request.claimable;
+    if amount.is_zero() {
+        return Err(ContractError::Std(StdError::generic_err(
+            "Nothing withdrawn from the house pool to claim",
+        )));
     }
 
-    let staker_fee = compute_gaming_fee(deps.as_ref(), gross)?;
-    ACCUMULATED_FEE.update(
-        deps.storage,
-        |fee_before| -> Result<u128, StdError> {
-            Ok(fee_before + staker_fee.u128())
+    request.claimable = Uint128::zero();
+    pool.unclaimed -= amount;
+    HOUSE_REQUESTS.save(deps.storage, &info.sender, &request)?;
+    HOUSE_POOL.save(deps.storage, &pool)?;
+
+    Ok(Response::new()
+        .add_message(get_asset_transfer_msg(
+            &config.stake_asset(),
+            &info.sender,
+            amount,
+        )?)
+        .add_event(
+            Event::new("hopers_bet")
+                .add_attribute("action", "claim-house")
+                .add_attribute("depositor", info.sender)
+                .add_attribute("amount", amount),
+        ))
+}
+
+/**
+ * A depositor's request, with what it queued for a settled epoch turned into
+ * shares and claimable funds; returns the shares it minted too
+ */
+fn load_house_request(
+    storage: &dyn Storage,
+    pool: &HousePool,
+    depositor: &Addr,
+) -> StdResult<(HouseRequest, Uint128)> {
+    let request = HOUSE_REQUESTS
+        .may_load(storage, depositor)?
+        .unwrap_or_default();
+    if request.epoch >= pool.epoch {
+        return Ok((request, Uint128::zero()));
+    }
+
+    let mut settled = HouseRequest {
+        epoch: pool.epoch,
+        claimable: request.claimable,
+        ..HouseRequest::default()
+    };
+    if request.deposit.is_zero() && request.shares.is_zero() {
+        return Ok((settled, Uint128::zero()));
+    }
+
+    let epoch = HOUSE_EPOCHS.load(storage, request.epoch)?;
+    let minted = if epoch.total_shares.is_zero() {
+        request.deposit
+    } else {
+        request
+            .deposit
+            .multiply_ratio(epoch.total_shares, epoch.nav)
+    };
+    if !request.shares.is_zero() {
+        settled.claimable +=
+            request.shares.multiply_ratio(epoch.nav, epoch.total_shares);
+    }
+    Ok((settled, minted))
+}
+
+/**
+ * Brings a depositor's request up to date, crediting the shares it minted
+ */
+fn apply_house_request(
+    storage: &mut dyn Storage,
+    pool: &HousePool,
+    depositor: &Addr,
+) -> StdResult<HouseRequest> {
+    let (request, minted) = load_house_request(storage, pool, depositor)?;
+    if !minted.is_zero() {
+        HOUSE_SHARES.update(
+            storage,
+            depositor,
+            |owned| -> StdResult<Uint128> {
+                Ok(owned.unwrap_or_default() + minted)
+            },
+        )?;
+    }
+    Ok(request)
+}
+
+/**
+ * Settles the deposits and withdrawals queued in the current epoch at the
+ * house pool's NAV; called once no started round is seeded, so nobody trades
+ * shares against a round whose outcome they can already see
+ */
+fn settle_house_epoch(storage: &mut dyn Storage) -> StdResult<()> {
+    let mut pool = HOUSE_POOL.may_load(storage)?.unwrap_or_default();
+    if pool.pending_deposits.is_zero() && pool.pending_shares.is_zero() {
+        return Ok(());
+    }
+
+    let nav = pool.nav();
+    /* Deposits wait while the shares have nothing left behind them */
+    if nav.is_zero()
+        && !pool.total_shares.is_zero()
+        && !pool.pending_deposits.is_zero()
+    {
+        return Ok(());
+    }
+    /* Withdrawals wait for the seeds to come back */
+    let withdrawn = compute_house_share_value(&pool, pool.pending_shares);
+    if withdrawn > pool.liquidity + pool.pending_deposits {
+        return Ok(());
+    }
+
+    let minted = if pool.total_shares.is_zero() {
+        pool.pending_deposits
+    } else {
+        pool.pending_deposits.multiply_ratio(pool.total_shares, nav)
+    };
+    HOUSE_EPOCHS.save(
+        storage,
+        pool.epoch,
+        &HouseEpoch {
+            nav,
+            total_shares: pool.total_shares,
         },
     )?;
 
-    /* Deduct open + burn fee from the gross amount */
-    let bet_amt = gross - staker_fee - burn_fee;
+    pool.liquidity = pool.liquidity + pool.pending_deposits - withdrawn;
+    pool.unclaimed += withdrawn;
+    pool.total_shares = pool.total_shares + minted - pool.pending_shares;
+    pool.pending_deposits = Uint128::zero();
+    pool.pending_shares = Uint128::zero();
+    pool.epoch += 1;
+    HOUSE_POOL.save(storage, &pool)
+}
+
+/**
+ * Seeds both sides of a new round out of the house pool's free liquidity
+ */
+fn seed_house(
+    storage: &mut dyn Storage,
+    config: &Config,
+) -> StdResult<Uint128> {
+    let mut pool = HOUSE_POOL.may_load(storage)?.unwrap_or_default();
+    /* Keeps what the queued withdrawals are worth for the next epoch */
+    let reserved = compute_house_share_value(&pool, pool.pending_shares);
+    let free = if reserved < pool.liquidity {
+        pool.liquidity - reserved
+    } else {
+        Uint128::zero()
+    };
+    let seed = config.house_seed_cap.min(free / Uint128::new(2));
+    if seed.is_zero() {
+        return Ok(seed);
+    }
+
+    pool.liquidity -= seed + seed;
+    pool.seeded += seed + seed;
+    HOUSE_POOL.save(storage, &pool)?;
+    Ok(seed)
+}
+
+/**
+ * Pays the house its seeds' share of a closed round, like any other bet
+ */
+fn settle_house_seed(
+    storage: &mut dyn Storage,
+    round: &FinishedRound,
+) -> StdResult<()> {
+    if round.house_seed.is_zero() {
+        return Ok(());
+    }
+
+    let pool = load_finished_round_pool(storage, round, &None)?;
+    let payout = match &round.winner {
+        Some(winner) => {
+            compute_prize_pool(round, pool.total(), pool.fee_amount)
+                .multiply_ratio(round.house_seed, pool.side_amount(winner))
+        }
+        /*
+         * Seeds pay no fee, so both refunds give them back; when the house
+         * takes the pool, they're its own
+         */
+        None => round.house_seed + round.house_seed,
+    };
+
+    let mut house = HOUSE_POOL.load(storage)?;
+    house.seeded -= round.house_seed + round.house_seed;
+    house.liquidity += payout;
+    HOUSE_POOL.save(storage, &house)
+}
+
+/**
+ * A staker with the rewards distributed since their last update made pending
+ */
+fn load_staker(storage: &dyn Storage, staker: &Addr) -> StdResult<StakerInfo> {
+    let state = STAKING_STATE.may_load(storage)?.unwrap_or_default();
+    let mut staker_info =
+        STAKERS.may_load(storage, staker)?.unwrap_or_default();
+
+    staker_info.pending_reward += staker_info.amount
+        * (state.reward_per_share - staker_info.reward_index);
+    staker_info.reward_index = state.reward_per_share;
+    Ok(staker_info)
+}
+
+fn execute_set_fee_recipients(
+    deps: DepsMut,
+    info: MessageInfo,
+    env: Env,
+    recipients: Vec<WalletInfo>,
+) -> Result<Response, ContractError> {
+    assert_is_admin(deps.as_ref(), info, env)?;
+
+    let mut total_ratio = Decimal::zero();
+    for recipient in recipients.iter() {
+        deps.api.addr_validate(recipient.address.as_str())?;
+        total_ratio = total_ratio + recipient.ratio;
+    }
+
+    if total_ratio != Decimal::one() {
+        return Err(ContractError::WrongRatio {});
+    }
+
+    FEE_RECIPIENTS.save(deps.storage, &recipients)?;
+
+    Ok(Response::new().add_event(
+        Event::new("hopers_bet")
+            .add_attribute("action", "set-fee-recipients")
+            .add_attribute("recipients", recipients.len().to_string()),
+    ))
+}
+
+fn execute_collect_winnings(
+    mut deps: DepsMut,
+    info: MessageInfo,
+    round_ids: Option<Vec<Uint128>>,
+    limit: Option<u32>,
+    player: Option<String>,
+    recipient: Option<String>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let mut payouts: Vec<AssetAmount> = vec![];
+    let mut resp = Response::new();
+
+    let player = match player {
+        Some(player) => deps.api.addr_validate(&player)?,
+        None => info.sender.clone(),
+    };
+    let recipient = match recipient {
+        Some(recipient) => deps.api.addr_validate(&recipient)?,
+        None => player.clone(),
+    };
+
+    if player != info.sender {
+        /* Operators can only claim into the player's own wallet */
+        if !CLAIM_OPERATORS
+            .may_load(deps.storage, (&player, &info.sender))?
+            .unwrap_or_default()
+        {
+            return Err(ContractError::Unauthorized {});
+        }
+        if recipient != player {
+            return Err(ContractError::Std(StdError::generic_err(
+                "Operators can't redirect winnings",
+            )));
+        }
+    }
+
+    let limit =
+        limit.unwrap_or(DEFAULT_CLAIM_LIMIT).min(MAX_CLAIM_LIMIT) as usize;
+    let claimed_bets = resolve_settled_bets(
+        deps.branch(),
+        &config,
+        &player,
+        round_ids,
+        limit,
+    )?;
+
+    for claimable in drain_claimable(deps.storage, &player)? {
+        add_payout(&mut payouts, claimable.info, claimable.amount);
+    }
+
+    if claimed_bets == 0 && payouts.is_empty() {
+        return Err(ContractError::Std(StdError::generic_err(
+            "Nothing to claim",
+        )));
+    }
+
+    /* Lost bets are cleared even though nothing is paid out */
+    resp = resp
+        .add_attribute("action", "collect-winnings")
+        .add_attribute("player", player.to_string())
+        .add_attribute("recipient", recipient.to_string())
+        .add_attribute("claimed_bets", claimed_bets.to_string());
+    for payout in payouts {
+        record_paid_out(deps.storage, &config, &payout.info, payout.amount)?;
+        let msg_send_winnings =
+            get_asset_transfer_msg(&payout.info, &recipient, payout.amount)?;
+        resp = resp
+            .add_message(msg_send_winnings)
+            .add_attribute("amount", payout.amount)
+            .add_attribute("token", payout.info.key());
+    }
+
+    Ok(resp)
+}
+
+fn execute_claim_referral_rewards(
+    deps: DepsMut,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let claimables = REFERRAL_CLAIMABLE
+        .prefix(&info.sender)
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let mut payouts = vec![];
+    for (key, claimable) in claimables {
+        REFERRAL_CLAIMABLE.remove(deps.storage, (&info.sender, &key));
+        if !claimable.amount.is_zero() {
+            payouts.push(claimable);
+        }
+    }
+
+    if payouts.is_empty() {
+        return Err(ContractError::Std(StdError::generic_err(
+            "Nothing to claim",
+        )));
+    }
+
+    let mut resp = Response::new()
+        .add_attribute("action", "claim-referral-rewards")
+        .add_attribute("recipient", info.sender.to_string());
+    for payout in payouts {
+        record_paid_out(deps.storage, &config, &payout.info, payout.amount)?;
+        resp = resp
+            .add_message(get_asset_transfer_msg(
+                &payout.info,
+                &info.sender,
+                payout.amount,
+            )?)
+            .add_attribute("amount", payout.amount)
+            .add_attribute("token", payout.info.key());
+    }
+
+    Ok(resp)
+}
+
+/**
+ * Empties the claimable balance of an address, returning what it held
+ */
+fn drain_claimable(
+    storage: &mut dyn Storage,
+    owner: &Addr,
+) -> StdResult<Vec<AssetAmount>> {
+    let claimables = CLAIMABLE
+        .prefix(owner)
+        .range(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let mut payouts = vec![];
+    for (key, claimable) in claimables {
+        CLAIMABLE.remove(storage, (owner, &key));
+        if !claimable.amount.is_zero() {
+            payouts.push(claimable);
+        }
+    }
+    Ok(payouts)
+}
+
+fn add_claimable(
+    storage: &mut dyn Storage,
+    owner: &Addr,
+    asset: &AssetInfo,
+    amount: Uint128,
+) -> StdResult<()> {
+    CLAIMABLE.update(
+        storage,
+        (owner, &asset.key()),
+        |claimable| -> StdResult<AssetAmount> {
+            let mut claimable = claimable.unwrap_or(AssetAmount {
+                info: asset.clone(),
+                amount: Uint128::zero(),
+            });
+            claimable.amount += amount;
+            Ok(claimable)
+        },
+    )?;
+    Ok(())
+}
+
+fn execute_settle_round(
+    deps: DepsMut,
+    round_id: Uint128,
+    limit: Option<u32>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    let round =
+        ROUNDS
+            .may_load(deps.storage, round_id.u128())?
+            .ok_or_else(|| {
+                StdError::generic_err(format!(
+                    "Round {} hasn't finished",
+                    round_id
+                ))
+            })?;
+
+    let limit =
+        limit.unwrap_or(DEFAULT_CLAIM_LIMIT).min(MAX_CLAIM_LIMIT) as usize;
+
+    /* Settled and claimed bets are already gone, whoever holds the rest */
+    let games = bet_info_storage()
+        .prefix(round_id.u128())
+        .range(deps.storage, None, None, Order::Ascending)
+        .take(limit)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let mut resp = Response::new();
+    for (player, game) in games.iter() {
+        let (asset, payout) = settle_bet(deps.storage, &config, &round, game)?;
+        /* Kept for whoever sends the position in */
+        if game.position_nft.is_some() {
+            POSITION_PAYOUTS.save(
+                deps.storage,
+                (round_id.u128(), player),
+                &AssetAmount {
+                    info: asset,
+                    amount: payout,
+                },
+            )?;
+            continue;
+        }
+        if !config.push_settlement {
+            if !payout.is_zero() {
+                add_claimable(deps.storage, player, &asset, payout)?;
+            }
+            continue;
+        }
+
+        /* Along with whatever the player's ledger holds */
+        let mut payouts: Vec<AssetAmount> = vec![];
+        for claimable in drain_claimable(deps.storage, player)? {
+            add_payout(&mut payouts, claimable.info, claimable.amount);
+        }
+        add_payout(&mut payouts, asset, payout);
+        for payout in payouts {
+            record_paid_out(
+                deps.storage,
+                &config,
+                &payout.info,
+                payout.amount,
+            )?;
+            resp = resp.add_message(get_asset_transfer_msg(
+                &payout.info,
+                player,
+                payout.amount,
+            )?);
+        }
+    }
+
+    Ok(resp.add_event(
+        Event::new("hopers_bet")
+            .add_attribute("action", "settle-round")
+            .add_attribute("round", round_id)
+            .add_attribute("settled_bets", games.len().to_string())
+            .add_attribute("done", (games.len() < limit).to_string()),
+    ))
+}
+
+fn execute_transfer_bet(
+    deps: DepsMut,
+    info: MessageInfo,
+    round_id: Uint128,
+    recipient: String,
+) -> Result<Response, ContractError> {
+    let recipient = deps.api.addr_validate(&recipient)?;
+
+    let sender_key = bet_info_key(round_id.u128(), &info.sender);
+    let mut bet_info = bet_info_storage()
+        .may_load(deps.storage, sender_key.clone())?
+        .ok_or_else(|| {
+            StdError::generic_err(format!(
+                "You have no bet for round {}",
+                round_id
+            ))
+        })?;
+
+    if bet_info.position_nft.is_some() {
+        return Err(ContractError::Std(StdError::generic_err(
+            "Transfer the position token of a tokenized bet instead",
+        )));
+    }
+
+    let recipient_key = bet_info_key(round_id.u128(), &recipient);
+    if bet_info_storage().has(deps.storage, recipient_key.clone()) {
+        return Err(ContractError::Std(StdError::generic_err(format!(
+            "{} already has a bet for round {}",
+            recipient, round_id
+        ))));
+    }
+
+    /* Re-keying keeps the player index in sync */
+    bet_info_storage().remove(deps.storage, sender_key)?;
+    bet_info.player = recipient.clone();
+    bet_info_storage().save(deps.storage, recipient_key, &bet_info)?;
+
+    Ok(Response::new().add_event(
+        Event::new("hopers_bet")
+            .add_attribute("action", "transfer-bet")
+            .add_attribute("round", round_id)
+            .add_attribute("from", info.sender)
+            .add_attribute("to", recipient),
+    ))
+}
+
+fn execute_receive_nft(
+    deps: DepsMut,
+    info: MessageInfo,
+    wrapper: Cw721ReceiveMsg,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let holder = deps.api.addr_validate(&wrapper.sender)?;
+    let (round_id, player) =
+        parse_position_token_id(deps.api, &wrapper.token_id)?;
+    let key = (round_id.u128(), &player);
+
+    /* Settled by a keeper already, or still to settle */
+    let parked = POSITION_PAYOUTS.may_load(deps.storage, key)?;
+    let game = match &parked {
+        Some(_) => SETTLED_BETS.load(deps.storage, key)?,
+        None => bet_info_storage()
+            .may_load(deps.storage, bet_info_key(round_id.u128(), &player))?
+            .ok_or_else(|| {
+                StdError::generic_err(format!(
+                    "Position {} was paid out already",
+                    wrapper.token_id
+                ))
+            })?,
+    };
+    if game.position_nft.as_ref() != Some(&info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let payout = match parked {
+        Some(payout) => {
+            POSITION_PAYOUTS.remove(deps.storage, key);
+            payout
+        }
+        None => {
+            let round = ROUNDS
+                .may_load(deps.storage, round_id.u128())?
+                .ok_or_else(|| {
+                    StdError::generic_err(format!(
+                        "Round {} hasn't finished",
+                        round_id
+                    ))
+                })?;
+            let (asset, amount) =
+                settle_bet(deps.storage, &config, &round, &game)?;
+            AssetAmount {
+                info: asset,
+                amount,
+            }
+        }
+    };
+
+    /* The market holds the position now, so it can burn it */
+    let mut resp = Response::new()
+        .add_message(get_position_burn_msg(
+            &info.sender,
+            wrapper.token_id.clone(),
+        )?)
+        .add_attribute("action", "claim-position")
+        .add_attribute("token_id", wrapper.token_id)
+        .add_attribute("amount", payout.amount)
+        .add_attribute("token", payout.info.key());
+    if !payout.amount.is_zero() {
+        record_paid_out(deps.storage, &config, &payout.info, payout.amount)?;
+        resp = resp.add_message(get_asset_transfer_msg(
+            &payout.info,
+            &holder,
+            payout.amount,
+        )?);
+    }
+
+    Ok(resp)
+}
+
+/**
+ * Moves what the player's finished bets are owed into their claimable
+ * balance; returns the number of bets resolved
+ */
+fn resolve_settled_bets(
+    deps: DepsMut,
+    config: &Config,
+    player: &Addr,
+    round_ids: Option<Vec<Uint128>>,
+    limit: usize,
+) -> StdResult<usize> {
+    let settled_games = query_my_settled_games(
+        deps.as_ref(),
+        player.clone(),
+        round_ids,
+        limit,
+    )?;
+
+    for (game, round) in settled_games.iter() {
+        let (asset, payout) = settle_bet(deps.storage, config, round, game)?;
+        if payout.is_zero() {
+            continue;
+        }
+        add_claimable(deps.storage, player, &asset, payout)?;
+    }
+
+    Ok(settled_games.len())
+}
+
+/**
+ * Removes a bet of a finished round and returns what it's owed, so it can
+ * only be paid out once
+ */
+fn settle_bet(
+    storage: &mut dyn Storage,
+    config: &Config,
+    round: &FinishedRound,
+    game: &BetInfo,
+) -> StdResult<(AssetInfo, Uint128)> {
+    bet_info_storage()
+        .remove(storage, bet_info_key(round.id.u128(), &game.player))?;
+    SETTLED_BETS.save(storage, (round.id.u128(), &game.player), game)?;
+
+    let payout = compute_settled_payout(storage, round, game)?;
+    if game.token.is_none() {
+        record_player_stats(storage, round, game, payout)?;
+    }
+
+    let asset = game.token.clone().unwrap_or_else(|| config.stake_asset());
+    Ok((asset, payout))
+}
+
+fn record_player_stats(
+    storage: &mut dyn Storage,
+    round: &FinishedRound,
+    game: &BetInfo,
+    payout: Uint128,
+) -> StdResult<()> {
+    let outcome = compute_settled_outcome(storage, round, game)?;
+    let old_stats =
+        player_stats_storage().may_load(storage, game.player.clone())?;
+    let mut stats = old_stats.clone().unwrap_or_default();
+
+    stats.rounds_played += 1;
+    match outcome {
+        BetOutcome::Win => {
+            stats.wins += 1;
+            stats.current_streak += 1;
+            stats.best_streak = stats.best_streak.max(stats.current_streak);
+        }
+        BetOutcome::Loss => {
+            stats.losses += 1;
+            stats.current_streak = 0;
+        }
+        BetOutcome::Tie => stats.ties += 1,
+    }
+    stats.total_staked += game.amount;
+    stats.total_won += payout;
+    stats.net_pnl += payout.u128() as i128 - game.amount.u128() as i128;
+
+    player_stats_storage().replace(
+        storage,
+        game.player.clone(),
+        Some(&stats),
+        old_stats.as_ref(),
+    )
+}
+
+fn execute_allow_claim_operator(
+    deps: DepsMut,
+    info: MessageInfo,
+    operator: String,
+) -> Result<Response, ContractError> {
+    let operator = deps.api.addr_validate(&operator)?;
+
+    CLAIM_OPERATORS.save(deps.storage, (&info.sender, &operator), &true)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "allow-claim-operator")
+        .add_attribute("player", info.sender)
+        .add_attribute("operator", operator))
+}
+
+fn execute_revoke_claim_operator(
+    deps: DepsMut,
+    info: MessageInfo,
+    operator: String,
+) -> Result<Response, ContractError> {
+    let operator = deps.api.addr_validate(&operator)?;
+
+    CLAIM_OPERATORS.remove(deps.storage, (&info.sender, &operator));
+
+    Ok(Response::new()
+        .add_attribute("action", "revoke-claim-operator")
+        .add_attribute("player", info.sender)
+        .add_attribute("operator", operator))
+}
+
+fn execute_receive(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    wrapper: Cw20ReceiveMsg,
+) -> Result<Response, ContractError> {
+    /* Whether the sending token may be bet is checked like any other bet */
+    let token = AssetInfo::Token {
+        contract_addr: info.sender,
+    };
+
+    let player = deps.api.addr_validate(&wrapper.sender)?;
+    let (round_id, side, referrer) = match from_binary(&wrapper.msg)? {
+        ReceiveMsg::BetBull { round_id, referrer } => {
+            (round_id, BetSide::Direction(Direction::Bull), referrer)
+        }
+        ReceiveMsg::BetBear { round_id, referrer } => {
+            (round_id, BetSide::Direction(Direction::Bear), referrer)
+        }
+        ReceiveMsg::BetBucket { round_id, bucket } => {
+            (round_id, BetSide::Bucket(bucket), None)
+        }
+        ReceiveMsg::Stake {} => {
+            return execute_stake(deps, env, token, player, wrapper.amount);
+        }
+        ReceiveMsg::DepositHouse {} => {
+            return execute_deposit_house(deps, token, player, wrapper.amount);
+        }
+    };
+
+    execute_bet(
+        deps,
+        env,
+        BetParams {
+            funding: BetFunding::Received(player),
+            round_id,
+            side,
+            gross: wrapper.amount,
+            token: Some(token),
+            referrer,
+        },
+    )
+}
+
+/**
+ * Where the stake of a bet comes from
+ */
+enum BetFunding {
+    /* An allowance on cw20 markets, the attached coins on native markets */
+    Wallet(MessageInfo),
+    /* Already sent to the contract through a cw20 `Send` */
+    Received(Addr),
+}
+
+impl BetFunding {
+    fn player(&self) -> &Addr {
+        match self {
+            BetFunding::Wallet(info) => &info.sender,
+            BetFunding::Received(player) => player,
+        }
+    }
+}
+
+/**
+ * What a bet is on; bull/bear rounds take a direction, range rounds a bucket
+ */
+enum BetSide {
+    Direction(Direction),
+    Bucket(u8),
+}
+
+/**
+ * A bet as it's placed, `gross` before fees
+ */
+struct BetParams {
+    funding: BetFunding,
+    round_id: Uint128,
+    side: BetSide,
+    gross: Uint128,
+    token: Option<AssetInfo>,
+    referrer: Option<String>,
+}
+
+fn execute_bet(
+    mut deps: DepsMut,
+    env: Env,
+    bet: BetParams,
+) -> Result<Response, ContractError> {
+    let BetParams {
+        funding,
+        round_id,
+        side,
+        gross,
+        token,
+        referrer,
+    } = bet;
+    assert_not_haulted(deps.as_ref())?;
+    let player = funding.player().clone();
+
+    let mut bet_round = load_bidding_round(deps.as_ref(), round_id)?;
+    let mut resp = Response::new();
+    let config = CONFIG.load(deps.storage)?;
+    /* Naming the market's own stake is the same as leaving it out */
+    let token = token.filter(|asset| *asset != config.stake_asset());
+    let stake = load_stake_token(deps.as_ref(), &token)?;
+
+    let bucket_bounds =
+        ROUND_BUCKETS.may_load(deps.storage, round_id.u128())?;
+    let (dir, bucket) = match (side, bucket_bounds) {
+        (BetSide::Direction(dir), None) => (dir, None),
+        (BetSide::Bucket(bucket), Some(bounds)) => {
+            if bucket as usize > bounds.len() {
+                return Err(ContractError::Std(StdError::generic_err(
+                    format!(
+                        "Round {} has buckets 0 to {}",
+                        round_id,
+                        bounds.len()
+                    ),
+                )));
+            }
+            if token.is_some() {
+                return Err(ContractError::Std(StdError::generic_err(
+                    "Range rounds only take the market's own stake",
+                )));
+            }
+            (bucket_direction(&bounds, bucket), Some(bucket))
+        }
+        (BetSide::Direction(_), Some(_)) => {
+            return Err(ContractError::Std(StdError::generic_err(format!(
+                "Round {} is a range round, bet on a bucket",
+                round_id
+            ))));
+        }
+        (BetSide::Bucket(_), None) => {
+            return Err(ContractError::Std(StdError::generic_err(format!(
+                "Round {} has no buckets",
+                round_id
+            ))));
+        }
+    };
+
+    assert_round_takes_bets(&env, &bet_round)?;
+    assert_minimum_bet(&stake, gross)?;
+
+    if let BetFunding::Wallet(info) = &funding {
+        assert_stake_funds(&stake.asset, info, gross)?;
+    }
+
+    let fee_tier = load_fee_tier(deps.as_ref(), &env, &player)?;
+    let discount = fee_tier
+        .as_ref()
+        .map(|tier| tier.discount)
+        .unwrap_or_default();
+    /* Rounds charging fees on winnings take nothing up front */
+    let fees_on_stake = bet_round.fee_model == FeeModel::OnStake;
+    let burn_fee = if fees_on_stake {
+        compute_burn_fee(&stake, gross, discount)?
+    } else {
+        Uint128::zero()
+    };
+
+    if burn_fee > Uint128::zero() {
+        let msg_burn_fee = match (&stake.asset, &funding) {
+            /* Native stakes are already held by the contract */
+            (AssetInfo::NativeToken { denom }, _) => {
+                get_bank_burn_msg(denom, burn_fee)?
+            }
+            (AssetInfo::Token { contract_addr }, BetFunding::Wallet(_)) => {
+                get_cw20_burn_from_msg(contract_addr, &player, burn_fee)?
+            }
+            (AssetInfo::Token { contract_addr }, BetFunding::Received(_)) => {
+                get_cw20_burn_msg(contract_addr, burn_fee)?
+            }
+        };
+        resp = resp.add_message(msg_burn_fee);
+    }
+
+    let gaming_fee = if fees_on_stake {
+        compute_gaming_fee(&stake, gross, discount)?
+    } else {
+        Uint128::zero()
+    };
+    let referrer = bind_referrer(deps.branch(), &player, referrer)?;
+    let referral_fee = match &referrer {
+        Some(referrer) => {
+            let referral_fee = compute_referral_fee(&config, gaming_fee)?;
+            credit_referral_fee(
+                deps.storage,
+                referrer,
+                &stake.asset,
+                referral_fee,
+            )?;
+            referral_fee
+        }
+        None => Uint128::zero(),
+    };
+    /*
+     * The referrer's share isn't refunded on ties; the rest stays in the
+     * round's pool until it finishes
+     */
+    let staker_fee = gaming_fee - referral_fee;
+
+    /* Deduct open + burn fee from the gross amount */
+    let bet_amt = gross - gaming_fee - burn_fee;
+
+    /*
+     * Keepers pay finished bets out under push settlement; otherwise a few
+     * are resolved here, keepers and claims take care of any backlog
+     */
+    if !config.push_settlement {
+        resolve_settled_bets(
+            deps.branch(),
+            &config,
+            &player,
+            None,
+            BET_RESOLVE_LIMIT,
+        )?;
+    }
+
+    let bet_info_key = bet_info_key(round_id.u128(), &player);
+
+    let bet_info =
+        bet_info_storage().may_load(deps.storage, bet_info_key.clone())?;
+
+    /* Betting again on the same side tops up the existing position */
+    let (prev_amount, prev_fee) = match bet_info {
+        Some(bet_info) => {
+            assert_can_increase_bet(&bet_info, &dir, &token, &bucket)?;
+            (bet_info.amount, bet_info.fee)
+        }
+        None => (Uint128::zero(), Uint128::zero()),
+    };
+    let round_total =
+        compute_players_round_total(deps.storage, &bet_round, &token)?;
+    assert_bet_limits(
+        &stake,
+        gross,
+        prev_amount + bet_amt,
+        round_total + bet_amt,
+    )?;
+    let action = if prev_amount.is_zero() {
+        "hopers-bet"
+    } else {
+        "hopers-bet-increase"
+    };
+
+    /* Only new bets are tokenized, top ups were refused above */
+    let position_nft = if prev_amount.is_zero() {
+        config.position_nft_addr.clone()
+    } else {
+        None
+    };
+    if let Some(nft_addr) = &position_nft {
+        resp = resp.add_message(get_position_mint_msg(
+            nft_addr,
+            &player,
+            PositionMetadata {
+                round_id,
+                direction: dir.clone(),
+                amount: bet_amt,
+                token: stake.asset.clone(),
+            },
+        )?);
+    }
+
+    bet_info_storage().save(
+        deps.storage,
+        bet_info_key,
+        &BetInfo {
+            player: player.clone(),
+            round_id,
+            amount: prev_amount + bet_amt,
+            direction: dir.clone(),
+            fee: prev_fee + staker_fee,
+            token: token.clone(),
+            position_nft,
+            bucket,
+        },
+    )?;
+
+    if prev_amount.is_zero() {
+        *bet_round.side_count_mut(&dir) += 1;
+    }
+    let mut pool = load_next_round_pool(deps.storage, &bet_round, &token)?;
+    pool.fee_amount += staker_fee;
+    let round_total = match bucket {
+        Some(bucket) => BUCKET_POOLS.update(
+            deps.storage,
+            (round_id.u128(), bucket),
+            |bucket_total| -> StdResult<Uint128> {
+                Ok(bucket_total.unwrap_or_default() + bet_amt)
+            },
+        )?,
+        None => {
+            *pool.side_amount_mut(&dir) += bet_amt;
+            pool.side_amount(&dir)
+        }
+    };
+    save_next_round_pool(deps.storage, &mut bet_round, &token, &pool)?;
+
+    if token.is_none() {
+        let new_player = !PLAYERS.has(deps.storage, &player);
+        if new_player {
+            PLAYERS.save(deps.storage, &player, &true)?;
+        }
+        update_global_stats(deps.storage, |stats| {
+            match dir {
+                Direction::Bull => stats.bull_volume += bet_amt,
+                Direction::Bear => stats.bear_volume += bet_amt,
+            }
+            if prev_amount.is_zero() {
+                stats.bet_count += 1;
+            }
+            if new_player {
+                stats.unique_players += 1;
+            }
+            stats.total_burned += burn_fee;
+        })?;
+        add_player_volume(deps.storage, &env, &player, bet_amt)?;
+    }
+
+    let round_total_key = match (bucket, &dir) {
+        (Some(_), _) => "round_bucket_total",
+        (None, Direction::Bull) => "round_bull_total",
+        (None, Direction::Bear) => "round_bear_total",
+    };
+    let mut event = Event::new("hopers_bet").add_attributes(vec![
+        ("action", action.to_string()),
+        ("round", round_id.to_string()),
+        ("direction", dir.to_string()),
+        ("amount", bet_amt.to_string()),
+        ("token", stake.asset.key()),
+        ("position", (prev_amount + bet_amt).to_string()),
+        (round_total_key, round_total.to_string()),
+        ("account", player.to_string()),
+    ]);
+    if let Some(bucket) = bucket {
+        event = event.add_attribute("bucket", bucket.to_string());
+    }
+    if let Some(tier) = fee_tier {
+        event = event.add_attribute("fee_tier", tier.name);
+    }
+    if let Some(referrer) = referrer {
+        event = event
+            .add_attribute("referrer", referrer.to_string())
+            .add_attribute("referral_fee", referral_fee.to_string());
+    }
+    resp = resp.add_event(event);
+
+    if let (AssetInfo::Token { contract_addr }, BetFunding::Wallet(_)) =
+        (&stake.asset, &funding)
+    {
+        let contract_addrss = env.contract.address;
+
+        let transfer_from_msg = get_cw20_transfer_from_msg(
+            contract_addr,
+            &player,
+            &contract_addrss,
+            //burn fee would be disappeared from user's wallet directly
+            gross - burn_fee,
+        )?;
+        resp = resp.add_message(transfer_from_msg);
+    }
+
+    Ok(resp)
+}
+
+/**
+ * The player's referrer, binding `referrer` if the player has none yet
+ */
+fn bind_referrer(
+    deps: DepsMut,
+    player: &Addr,
+    referrer: Option<String>,
+) -> StdResult<Option<Addr>> {
+    if let Some(bound) = REFERRERS.may_load(deps.storage, player)? {
+        return Ok(Some(bound));
+    }
+    let referrer = match referrer {
+        Some(referrer) => deps.api.addr_validate(&referrer)?,
+        None => return Ok(None),
+    };
+    if referrer == *player {
+        return Err(StdError::generic_err("You can't refer yourself"));
+    }
+
+    REFERRERS.save(deps.storage, player, &referrer)?;
+    REFERRALS.save(deps.storage, (&referrer, player), &true)?;
+    Ok(Some(referrer))
+}
+
+fn credit_referral_fee(
+    storage: &mut dyn Storage,
+    referrer: &Addr,
+    asset: &AssetInfo,
+    amount: Uint128,
+) -> StdResult<()> {
+    if amount.is_zero() {
+        return Ok(());
+    }
+    for ledger in [REFERRAL_CLAIMABLE, REFERRAL_EARNINGS] {
+        ledger.update(
+            storage,
+            (referrer, &asset.key()),
+            |earnings| -> StdResult<AssetAmount> {
+                let mut earnings = earnings.unwrap_or(AssetAmount {
+                    info: asset.clone(),
+                    amount: Uint128::zero(),
+                });
+                earnings.amount += amount;
+                Ok(earnings)
+            },
+        )?;
+    }
+    Ok(())
+}
+
+fn assert_round_takes_bets(env: &Env, round: &NextRound) -> StdResult<()> {
+    if env.block.time > round.open_time {
+        return Err(StdError::generic_err(format!(
+            "Round {} stopped accepting bids {} second(s) ago; the next round has not yet begun", round.id,
+                (env.block.time.seconds() - round.open_time.seconds())
+        )));
+    }
+    Ok(())
+}
+
+fn assert_minimum_bet(
+    stake: &StakeToken,
+    gross: Uint128,
+) -> Result<(), ContractError> {
+    if gross < stake.minimum_bet {
+        return Err(ContractError::BetTooSmall {
+            minimum: stake.minimum_bet,
+        });
+    }
+    Ok(())
+}
+
+/**
+ * Caps of bets in a stake; `position` and `round_total` include the bet
+ */
+fn assert_bet_limits(
+    stake: &StakeToken,
+    gross: Uint128,
+    position: Uint128,
+    round_total: Uint128,
+) -> Result<(), ContractError> {
+    if !stake.maximum_bet.is_zero() && gross > stake.maximum_bet {
+        return Err(ContractError::BetTooLarge {
+            maximum: stake.maximum_bet,
+        });
+    }
+    if !stake.maximum_position.is_zero() && position > stake.maximum_position {
+        return Err(ContractError::PositionTooLarge {
+            maximum: stake.maximum_position,
+        });
+    }
+    if !stake.maximum_round_pool.is_zero()
+        && round_total > stake.maximum_round_pool
+    {
+        return Err(ContractError::RoundPoolFull {
+            maximum: stake.maximum_round_pool,
+        });
+    }
+    Ok(())
+}
+
+/**
+ * The minimum bet can't exceed any of a stake's caps
+ */
+fn assert_valid_bet_limits(stake: &StakeToken) -> Result<(), ContractError> {
+    if [
+        stake.maximum_bet,
+        stake.maximum_position,
+        stake.maximum_round_pool,
+    ]
+    .iter()
+    .any(|maximum| !maximum.is_zero() && stake.minimum_bet > *maximum)
+    {
+        return Err(ContractError::InvalidBetLimits {});
+    }
+    Ok(())
+}
+
+/**
+ * Net amount players bet in a stake in a round
+ */
+fn compute_players_round_total(
+    storage: &dyn Storage,
+    round: &NextRound,
+    token: &Option<AssetInfo>,
+) -> StdResult<Uint128> {
+    if token.is_some() {
+        return Ok(load_next_round_pool(storage, round, token)?.total());
+    }
+    if ROUND_BUCKETS.has(storage, round.id.u128()) {
+        return Ok(load_bucket_pools(storage, round.id)?
+            .iter()
+            .fold(Uint128::zero(), |total, (_, amount)| total + amount));
+    }
+    Ok(round.bull_amount + round.bear_amount
+        - round.house_seed
+        - round.house_seed)
+}
+
+/**
+ * Betting again on the same side tops up the existing position
+ */
+fn assert_can_increase_bet(
+    bet_info: &BetInfo,
+    dir: &Direction,
+    token: &Option<AssetInfo>,
+    bucket: &Option<u8>,
+) -> StdResult<()> {
+    if bet_info.position_nft.is_some() {
+        return Err(StdError::generic_err(
+            "Tokenized positions can't be increased",
+        ));
+    }
+    if bet_info.direction != *dir
+        || bet_info.token != *token
+        || bet_info.bucket != *bucket
+    {
+        return Err(StdError::generic_err(format!(
+            "You are already bet for this game for {}, with amount: {}",
+            bet_info.direction.to_string(),
+            bet_info.amount
+        )));
+    }
+    Ok(())
+}
+
+fn execute_withdraw_bet(
+    deps: DepsMut,
+    info: MessageInfo,
+    env: Env,
+    round_id: Uint128,
+    amount: Option<Uint128>,
+) -> Result<Response, ContractError> {
+    assert_not_haulted(deps.as_ref())?;
+
+    let mut bet_round = load_bidding_round(deps.as_ref(), round_id)?;
+    let config = CONFIG.load(deps.storage)?;
+
+    if env.block.time > bet_round.open_time {
+        return Err(ContractError::Std(StdError::generic_err(format!(
+            "Round {} is locked; bets can no longer be withdrawn",
+            round_id
+        ))));
+    }
+
+    let bet_info_key = bet_info_key(round_id.u128(), &info.sender);
+    let mut bet_info = bet_info_storage()
+        .may_load(deps.storage, bet_info_key.clone())?
+        .ok_or_else(|| {
+            StdError::generic_err(format!(
+                "You have no bet for round {}",
+                round_id
+            ))
+        })?;
+
+    if bet_info.position_nft.is_some() {
+        return Err(ContractError::Std(StdError::generic_err(
+            "Tokenized positions can't be withdrawn",
+        )));
+    }
+
+    let withdraw_amt = amount.unwrap_or(bet_info.amount);
+    if withdraw_amt.is_zero() || withdraw_amt > bet_info.amount {
+        return Err(ContractError::Std(StdError::generic_err(format!(
+            "Can withdraw between 1 and {}",
+            bet_info.amount
+        ))));
+    }
+    /* What's left has to be worth a bet on its own */
+    let minimum_bet = match &bet_info.token {
+        Some(asset) => {
+            load_known_stake_token(deps.storage, &asset.key())?.minimum_bet
+        }
+        None => config.minimum_bet,
+    };
+    if withdraw_amt < bet_info.amount
+        && bet_info.amount - withdraw_amt < minimum_bet
+    {
+        return Err(ContractError::PositionTooSmall {
+            minimum: minimum_bet,
+        });
+    }
+
+    let penalty = compute_withdrawal_penalty(deps.as_ref(), withdraw_amt)?;
+    /* The fee paid on the withdrawn part is no longer refundable on a tie */
+    let released_fee =
+        bet_info.fee.multiply_ratio(withdraw_amt, bet_info.amount);
+
+    let token = bet_info.token.clone();
+    let closed = withdraw_amt == bet_info.amount;
+    if closed {
+        /* Rounds opened before the counts existed start them at zero */
+        let count = bet_round.side_count_mut(&bet_info.direction);
+        *count = count.saturating_sub(1);
+    }
+    let mut pool = load_next_round_pool(deps.storage, &bet_round, &token)?;
+    pool.fee_amount -= released_fee;
+    let round_total = match bet_info.bucket {
+        Some(bucket) => BUCKET_POOLS.update(
+            deps.storage,
+            (round_id.u128(), bucket),
+            |bucket_total| -> StdResult<Uint128> {
+                Ok(bucket_total.unwrap_or_default() - withdraw_amt)
+            },
+        )?,
+        None => {
+            *pool.side_amount_mut(&bet_info.direction) -= withdraw_amt;
+            pool.side_amount(&bet_info.direction)
+        }
+    };
+    save_next_round_pool(deps.storage, &mut bet_round, &token, &pool)?;
+
+    if closed {
+        bet_info_storage().remove(deps.storage, bet_info_key)?;
+    } else {
+        bet_info.amount -= withdraw_amt;
+        bet_info.fee -= released_fee;
+        bet_info_storage().save(deps.storage, bet_info_key, &bet_info)?;
+    }
+
+    add_accumulated_fee(deps.storage, &token, released_fee + penalty)?;
+    if token.is_none() {
+        let direction = bet_info.direction.clone();
+        /* Bets placed before the stats existed were never counted */
+        update_global_stats(deps.storage, |stats| {
+            let volume = match direction {
+                Direction::Bull => &mut stats.bull_volume,
+                Direction::Bear => &mut stats.bear_volume,
+            };
+            *volume = volume.saturating_sub(withdraw_amt);
+            if closed {
+                stats.bet_count = stats.bet_count.saturating_sub(1);
+            }
+        })?;
+        sub_player_volume(deps.storage, &env, &info.sender, withdraw_amt)?;
+    }
+
+    let asset = token.unwrap_or_else(|| config.stake_asset());
+    let mut resp = Response::new().add_event(
+        Event::new("hopers_bet").add_attributes(vec![
+            ("action", "hopers-bet-withdraw".to_string()),
+            ("round", round_id.to_string()),
+            ("direction", bet_info.direction.to_string()),
+            ("amount", withdraw_amt.to_string()),
+            ("token", asset.key()),
+            ("penalty", penalty.to_string()),
+            ("round_total", round_total.to_string()),
+            ("account", info.sender.to_string()),
+        ]),
+    );
+
+    let refund = withdraw_amt - penalty;
+    if refund > Uint128::zero() {
+        resp = resp.add_message(get_asset_transfer_msg(
+            &asset,
+            &info.sender,
+            refund,
+        )?);
+    }
+
+    Ok(resp)
+}
+
+fn execute_set_stake_token(
+    deps: DepsMut,
+    info: MessageInfo,
+    env: Env,
+    token: StakeToken,
+) -> Result<Response, ContractError> {
+    assert_is_admin(deps.as_ref(), info, env)?;
+    let config = CONFIG.load(deps.storage)?;
+
+    if token.asset == config.stake_asset() {
+        return Err(ContractError::Std(StdError::generic_err(
+            "The market's own stake can't be whitelisted",
+        )));
+    }
+    if let AssetInfo::Token { contract_addr } = &token.asset {
+        deps.api.addr_validate(contract_addr.as_str())?;
+    }
+    if let AssetInfo::NativeToken { denom } = &token.asset {
+        assert_valid_denom(denom)?;
+    }
+    if token.burn_fee + token.gaming_fee > Uint128::new(FEE_PRECISION * 100) {
+        return Err(ContractError::FeesTooHigh {});
+    }
+    assert_valid_bet_limits(&token)?;
+
+    STAKE_TOKENS.save(deps.storage, &token.asset.key(), &token)?;
+    KNOWN_STAKE_TOKENS.save(deps.storage, &token.asset.key(), &token)?;
+
+    Ok(Response::new().add_event(
+        Event::new("hopers_bet")
+            .add_attribute("action", "set-stake-token")
+            .add_attribute("token", token.asset.key()),
+    ))
+}
+
+fn execute_remove_stake_token(
+    deps: DepsMut,
+    info: MessageInfo,
+    env: Env,
+    asset: AssetInfo,
+) -> Result<Response, ContractError> {
+    assert_is_admin(deps.as_ref(), info, env)?;
+
+    STAKE_TOKENS.remove(deps.storage, &asset.key());
+
+    Ok(Response::new().add_event(
+        Event::new("hopers_bet")
+            .add_attribute("action", "remove-stake-token")
+            .add_attribute("token", asset.key()),
+    ))
+}
+
+fn execute_set_fee_tiers(
+    deps: DepsMut,
+    info: MessageInfo,
+    env: Env,
+    tiers: Vec<FeeTier>,
+) -> Result<Response, ContractError> {
+    assert_is_admin(deps.as_ref(), info, env)?;
+
+    for tier in tiers.iter() {
+        if tier.discount > Uint128::new(FEE_PRECISION * 100) {
+            return Err(ContractError::Std(StdError::generic_err(format!(
+                "Fee tier {} discounts more than the fees",
+                tier.name
+            ))));
+        }
+        match &tier.requirement {
+            FeeTierRequirement::Cw721Count { count, .. }
+                if *count > MAX_CW721_COUNT =>
+            {
+                return Err(ContractError::Std(StdError::generic_err(
+                    format!(
+                        "Fee tier {} can require at most {} tokens",
+                        tier.name, MAX_CW721_COUNT
+                    ),
+                )));
+            }
+            FeeTierRequirement::Cw20Balance { contract_addr, .. }
+            | FeeTierRequirement::Cw721Count { contract_addr, .. } => {
+                deps.api.addr_validate(contract_addr.as_str())?;
+            }
+            FeeTierRequirement::Volume { .. } => {}
+        }
+    }
+
+    FEE_TIERS.save(deps.storage, &tiers)?;
+
+    Ok(Response::new().add_event(
+        Event::new("hopers_bet")
+            .add_attribute("action", "set-fee-tiers")
+            .add_attribute("tiers", tiers.len().to_string()),
+    ))
+}
+
+/**
+ * The best fee tier the player meets, if any
+ */
+fn load_fee_tier(
+    deps: Deps,
+    env: &Env,
+    player: &Addr,
+) -> StdResult<Option<FeeTier>> {
+    let mut tiers = FEE_TIERS.may_load(deps.storage)?.unwrap_or_default();
+    /* Holdings are only queried until a tier is met */
+    tiers.sort_by(|a, b| b.discount.cmp(&a.discount));
+
+    for tier in tiers {
+        if meets_fee_tier(deps, env, player, &tier.requirement)? {
+            return Ok(Some(tier));
+        }
+    }
+    Ok(None)
+}
+
+fn meets_fee_tier(
+    deps: Deps,
+    env: &Env,
+    player: &Addr,
+    requirement: &FeeTierRequirement,
+) -> StdResult<bool> {
+    match requirement {
+        FeeTierRequirement::Volume { amount } => {
+            Ok(query_trailing_volume(deps.storage, env, player)? >= *amount)
+        }
+        FeeTierRequirement::Cw20Balance {
+            contract_addr,
+            amount,
+        } => {
+            let balance: BalanceResponse = deps.querier.query_wasm_smart(
+                contract_addr.to_string(),
+                &Cw20QueryMsg::Balance {
+                    address: player.to_string(),
+                },
+            )?;
+            Ok(balance.balance >= *amount)
+        }
+        FeeTierRequirement::Cw721Count {
+            contract_addr,
+            count,
+        } => {
+            let tokens: TokensResponse = deps.querier.query_wasm_smart(
+                contract_addr.to_string(),
+                &PositionNftQueryMsg::Tokens {
+                    owner: player.to_string(),
+                    start_after: None,
+                    limit: Some(*count),
+                },
+            )?;
+            Ok(tokens.tokens.len() as u32 >= *count)
+        }
+    }
+}
+
+fn volume_epoch(env: &Env) -> u64 {
+    env.block.time.seconds() / VOLUME_EPOCH_SECONDS
+}
+
+/**
+ * Net stake the player bet in the market's stake over the volume window
+ */
+fn query_trailing_volume(
+    storage: &dyn Storage,
+    env: &Env,
+    player: &Addr,
+) -> StdResult<Uint128> {
+    let start = (volume_epoch(env) + 1).saturating_sub(VOLUME_EPOCHS);
+    PLAYER_VOLUME
+        .prefix(player)
+        .range(
+            storage,
+            Some(Bound::inclusive(start)),
+            None,
+            Order::Ascending,
+        )
+        .try_fold(Uint128::zero(), |total, res| {
+            res.map(|(_, amount)| total + amount)
+        })
+}
+
+fn add_player_volume(
+    storage: &mut dyn Storage,
+    env: &Env,
+    player: &Addr,
+    amount: Uint128,
+) -> StdResult<()> {
+    /* Epochs that left the window are dropped as the player bets again */
+    let start = (volume_epoch(env) + 1).saturating_sub(VOLUME_EPOCHS);
+    let stale_epochs = PLAYER_VOLUME
+        .prefix(player)
+        .keys(
+            storage,
+            None,
+            Some(Bound::exclusive(start)),
+            Order::Ascending,
+        )
+        .collect::<StdResult<Vec<_>>>()?;
+    for epoch in stale_epochs {
+        PLAYER_VOLUME.remove(storage, (player, epoch));
+    }
+
+    PLAYER_VOLUME.update(
+        storage,
+        (player, volume_epoch(env)),
+        |volume| -> StdResult<Uint128> {
+            Ok(volume.unwrap_or_default() + amount)
+        },
+    )?;
+    Ok(())
+}
+
+/**
+ * Withdrawn bets don't count towards volume; bets are withdrawn before
+ * their round locks, so within the epoch they were placed in
+ */
+fn sub_player_volume(
+    storage: &mut dyn Storage,
+    env: &Env,
+    player: &Addr,
+    amount: Uint128,
+) -> StdResult<()> {
+    let key = (player, volume_epoch(env));
+    if let Some(volume) = PLAYER_VOLUME.may_load(storage, key)? {
+        PLAYER_VOLUME.save(storage, key, &volume.saturating_sub(amount))?;
+    }
+    Ok(())
+}
+
+/**
+ * Minimum and fees of the token a bet is placed in
+ */
+fn load_stake_token(
+    deps: Deps,
+    token: &Option<AssetInfo>,
+) -> StdResult<StakeToken> {
+    match token {
+        Some(asset) => STAKE_TOKENS
+            .may_load(deps.storage, &asset.key())?
+            .ok_or_else(|| {
+                StdError::generic_err(format!(
+                    "{} can't be bet in this market",
+                    asset.key()
+                ))
+            }),
+        None => Ok(CONFIG.load(deps.storage)?.stake_token()),
+    }
+}
+
+/**
+ * A token's last whitelisted settings, whether or not it's still whitelisted
+ */
+fn load_known_stake_token(
+    storage: &dyn Storage,
+    key: &str,
+) -> StdResult<StakeToken> {
+    match KNOWN_STAKE_TOKENS.may_load(storage, key)? {
+        Some(stake_token) => Ok(stake_token),
+        None => STAKE_TOKENS.load(storage, key),
+    }
+}
+
+fn load_next_round_pool(
+    storage: &dyn Storage,
+    round: &NextRound,
+    token: &Option<AssetInfo>,
+) -> StdResult<RoundPool> {
+    match token {
+        Some(asset) => Ok(ROUND_POOLS
+            .may_load(storage, (round.id.u128(), &asset.key()))?
+            .unwrap_or_default()),
+        None => Ok(RoundPool {
+            bull_amount: round.bull_amount,
+            bear_amount: round.bear_amount,
+            fee_amount: round.fee_amount,
+        }),
+    }
+}
+
+fn save_next_round_pool(
+    storage: &mut dyn Storage,
+    round: &mut NextRound,
+    token: &Option<AssetInfo>,
+    pool: &RoundPool,
+) -> StdResult<()> {
+    match token {
+        Some(asset) => {
+            let key = asset.key();
+            if round.fee_model == FeeModel::OnWinnings
+                && !ROUND_POOL_FEE_RATES.has(storage, (round.id.u128(), &key))
+            {
+                let stake_token = load_known_stake_token(storage, &key)?;
+                ROUND_POOL_FEE_RATES.save(
+                    storage,
+                    (round.id.u128(), &key),
+                    &stake_token.gaming_fee,
+                )?;
+            }
+            ROUND_POOLS.save(storage, (round.id.u128(), &key), pool)?;
+        }
+        None => {
+            round.bull_amount = pool.bull_amount;
+            round.bear_amount = pool.bear_amount;
+            round.fee_amount = pool.fee_amount;
+        }
+    }
+
+    /* Bettor counts span all tokens */
+    if round.id.u128() < STRIKE_MARKET_ID_OFFSET {
+        return NEXT_ROUND.save(storage, round);
+    }
+    STRIKE_MARKETS.update(
+        storage,
+        round.id.u128(),
+        |market| -> StdResult<StrikeMarket> {
+            let mut market =
+                market.ok_or_else(|| StdError::not_found("strike market"))?;
+            market.bull_amount = round.bull_amount;
+            market.bear_amount = round.bear_amount;
+            market.fee_amount = round.fee_amount;
+            market.bull_count = round.bull_count;
+            market.bear_count = round.bear_count;
+            Ok(market)
+        },
+    )?;
+    Ok(())
+}
+
+/**
+ * The pool a settled bet is paid from
+ */
+fn load_finished_round_pool(
+    storage: &dyn Storage,
+    round: &FinishedRound,
+    token: &Option<AssetInfo>,
+) -> StdResult<RoundPool> {
+    match token {
+        Some(asset) => Ok(ROUND_POOLS
+            .may_load(storage, (round.id.u128(), &asset.key()))?
+            .unwrap_or_default()),
+        None => Ok(RoundPool {
+            bull_amount: round.bull_amount,
+            bear_amount: round.bear_amount,
+            fee_amount: round.fee_amount,
+        }),
+    }
+}
+
+fn add_accumulated_fee(
+    storage: &mut dyn Storage,
+    token: &Option<AssetInfo>,
+    amount: Uint128,
+) -> StdResult<()> {
+    match token {
+        Some(asset) => {
+            TOKEN_ACCUMULATED_FEE.update(
+                storage,
+                &asset.key(),
+                |fee_before| -> StdResult<u128> {
+                    Ok(fee_before.unwrap_or_default() + amount.u128())
+                },
+            )?;
+        }
+        None => {
+            ACCUMULATED_FEE.update(
+                storage,
+                |fee_before| -> Result<u128, StdError> {
+                    Ok(fee_before + amount.u128())
+                },
+            )?;
+            update_global_stats(storage, |stats| {
+                stats.total_fees_accrued += amount
+            })?;
+        }
+    }
+    Ok(())
+}
+
+fn update_global_stats(
+    storage: &mut dyn Storage,
+    update: impl FnOnce(&mut GlobalStats),
+) -> StdResult<()> {
+    let mut stats = GLOBAL_STATS.may_load(storage)?.unwrap_or_default();
+    update(&mut stats);
+    GLOBAL_STATS.save(storage, &stats)
+}
+
+fn record_paid_out(
+    storage: &mut dyn Storage,
+    config: &Config,
+    asset: &AssetInfo,
+    amount: Uint128,
+) -> StdResult<()> {
+    if *asset != config.stake_asset() {
+        return Ok(());
+    }
+    update_global_stats(storage, |stats| stats.total_paid_out += amount)
+}
+
+/**
+ * Adds a payout to the list of transfers, one per token
+ */
+fn add_payout(
+    payouts: &mut Vec<AssetAmount>,
+    info: AssetInfo,
+    amount: Uint128,
+) {
+    if amount.is_zero() {
+        return;
+    }
+    match payouts.iter_mut().find(|payout| payout.info == info) {
+        Some(payout) => payout.amount += amount,
+        None => payouts.push(AssetAmount { info, amount }),
+    }
+}
+
+fn execute_create_strike_market(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    strike_price: Uint128,
+    cutoff_time: Timestamp,
+    expiry_time: Timestamp,
+) -> Result<Response, ContractError> {
+    assert_not_haulted(deps.as_ref())?;
+    let config = CONFIG.load(deps.storage)?;
+    if !STRIKE_CREATORS
+        .may_load(deps.storage, &info.sender)?
+        .unwrap_or_default()
+    {
+        assert_is_admin(deps.as_ref(), info.clone(), env.clone())?;
+    }
+
+    if strike_price.is_zero()
+        || cutoff_time <= env.block.time
+        || expiry_time < cutoff_time
+    {
+        return Err(ContractError::Std(StdError::generic_err(
+            "Strike markets need a strike and a cutoff before the expiry",
+        )));
+    }
+
+    let id = NEXT_STRIKE_MARKET_ID
+        .may_load(deps.storage)?
+        .unwrap_or(STRIKE_MARKET_ID_OFFSET);
+    NEXT_STRIKE_MARKET_ID.save(deps.storage, &(id + 1u128))?;
+
+    STRIKE_MARKETS.save(
+        deps.storage,
+        id,
+        &StrikeMarket {
+            id: Uint128::new(id),
+            creator: info.sender.clone(),
+            strike_price,
+            bid_time: env.block.time,
+            cutoff_time,
+            expiry_time,
+            bull_amount: Uint128::zero(),
+            bear_amount: Uint128::zero(),
+            fee_amount: Uint128::zero(),
+            bull_count: 0,
+            bear_count: 0,
+            winnings_fee_rate: snapshot_winnings_fee_rate(&config),
+            fee_model: config.fee_model,
+        },
+    )?;
+
+    Ok(
+        Response::new().add_event(Event::new("hopers_bet").add_attributes(
+            vec![
+                ("action", "create-strike-market".to_string()),
+                ("market", id.to_string()),
+                ("strike_price", strike_price.to_string()),
+                ("cutoff_time", cutoff_time.seconds().to_string()),
+                ("expiry_time", expiry_time.seconds().to_string()),
+                ("creator", info.sender.to_string()),
+            ],
+        )),
+    )
+}
+
+fn execute_settle_strike_market(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    market_id: Uint128,
+) -> Result<Response, ContractError> {
+    assert_not_haulted(deps.as_ref())?;
+    let config = CONFIG.load(deps.storage)?;
+
+    let market = STRIKE_MARKETS
+        .may_load(deps.storage, market_id.u128())?
+        .ok_or_else(|| {
+            StdError::generic_err(format!("No strike market {}", market_id))
+        })?;
+
+    if env.block.time < market.expiry_time {
+        return Err(ContractError::Std(StdError::generic_err(format!(
+            "Strike market {} expires in {} second(s)",
+            market_id,
+            market.expiry_time.seconds() - env.block.time.seconds()
+        ))));
+    }
+    if ROUNDS.has(deps.storage, market_id.u128()) {
+        return Err(ContractError::Std(StdError::generic_err(format!(
+            "Strike market {} is already settled",
+            market_id
+        ))));
+    }
+
+    /*
+     * The oracle only knows the latest price, so it only stands for the
+     * price at expiry shortly after; later settlements refund everybody
+     * rather than let anyone pick the price. Within the window only those
+     * who can open markets pick the moment, after it anybody can refund
+     */
+    let late = env.block.time.seconds()
+        > market.expiry_time.seconds() + STRIKE_SETTLE_WINDOW_SECONDS;
+    if !late
+        && !STRIKE_CREATORS
+            .may_load(deps.storage, &info.sender)?
+            .unwrap_or_default()
+    {
+        assert_is_admin(deps.as_ref(), info, env.clone())?;
+    }
+    let close_price = get_current_price(deps.as_ref())?;
+    let (winner, tie_policy) = if late {
+        (None, TiePolicy::RefundGross)
+    } else if close_price > market.strike_price {
+        (Some(Direction::Bull), config.tie_policy)
+    } else if close_price < market.strike_price {
+        (Some(Direction::Bear), config.tie_policy)
+    } else {
+        (None, config.tie_policy)
+    };
+
+    let mut finished_round = FinishedRound {
+        id: market.id,
+        bid_time: market.bid_time,
+        open_time: market.cutoff_time,
+        close_time: market.expiry_time,
+        open_price: market.strike_price,
+        close_price,
+        winner,
+        bull_amount: market.bull_amount,
+        bear_amount: market.bear_amount,
+        fee_amount: market.fee_amount,
+        tie_policy,
+        winning_bucket: None,
+        bull_count: market.bull_count,
+        bear_count: market.bear_count,
+        house_seed: Uint128::zero(),
+        fee_model: market.fee_model,
+        winnings_fee_rate: market.winnings_fee_rate,
+    };
+    apply_winnings_fees(deps.storage, &mut finished_round)?;
+    apply_round_tie_policies(deps.storage, &finished_round)?;
+    ROUNDS.save(deps.storage, market_id.u128(), &finished_round)?;
 
-    let bet_info_key = bet_info_key(round_id.u128(), &info.sender.clone());
+    Ok(
+        Response::new().add_event(Event::new("hopers_bet").add_attributes(
+            vec![
+                ("action", "settle-strike-market".to_string()),
+                ("market", market_id.to_string()),
+                ("close_price", close_price.to_string()),
+                ("late", late.to_string()),
+                (
+                    "winner",
+                    match finished_round.winner {
+                        Some(w) => w.to_string(),
+                        None => "everybody".to_string(),
+                    },
+                ),
+            ],
+        )),
+    )
+}
 
-    let bet_info =
-        bet_info_storage().may_load(deps.storage, bet_info_key.clone())?;
+fn execute_set_strike_creator(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    creator: String,
+    allowed: bool,
+) -> Result<Response, ContractError> {
+    assert_is_admin(deps.as_ref(), info, env)?;
+    let creator = deps.api.addr_validate(&creator)?;
 
-    if !bet_info.is_none() {
-        return Err(ContractError::Std(StdError::generic_err(format!(
-            "You are already bet for this game for {}, with amount: {}",
-            bet_info.clone().unwrap().direction.to_string(),
-            bet_info.unwrap().amount
-        ))));
+    if allowed {
+        STRIKE_CREATORS.save(deps.storage, &creator, &true)?;
+    } else {
+        STRIKE_CREATORS.remove(deps.storage, &creator);
     }
 
-    match dir {
-        Direction::Bull => {
-            // BULL_BETS.save(deps.storage, bet_key, &bet_amt.u128())?;
-            bet_info_storage().save(
-                deps.storage,
-                bet_info_key.clone(),
-                &BetInfo {
-                    player: info.sender.clone(),
-                    round_id,
-                    amount: bet_amt,
-                    direction: Direction::Bull,
-                },
-            )?;
-            bet_round.bull_amount += bet_amt;
-            NEXT_ROUND.save(deps.storage, &bet_round)?;
-            resp =
-                resp.add_event(Event::new("hopers_bet").add_attributes(vec![
-                    ("action", "hopers-bet".to_string()),
-                    ("round", round_id.to_string()),
-                    ("direction", "bull".to_string()),
-                    ("amount", bet_amt.to_string()),
-                    ("round_bull_total", bet_round.bull_amount.to_string()),
-                    ("account", info.sender.to_string()),
-                ]));
-        }
-        Direction::Bear => {
-            bet_info_storage().save(
-                deps.storage,
-                bet_info_key.clone(),
-                &BetInfo {
-                    player: info.sender.clone(),
-                    round_id,
-                    amount: bet_amt,
-                    direction: Direction::Bear,
-                },
-            )?;
-            bet_round.bear_amount += bet_amt;
-            NEXT_ROUND.save(deps.storage, &bet_round)?;
-            resp =
-                resp.add_event(Event::new("hopers_bet").add_attributes(vec![
-                    ("action", "hopers-bet".to_string()),
-                    ("round", round_id.to_string()),
-                    ("direction", "bear".to_string()),
-                    ("amount", bet_amt.to_string()),
-                    ("round_bear_total", bet_round.bear_amount.to_string()),
-                    ("account", info.sender.to_string()),
-                ]));
-        }
-    }
-
-    let contract_addrss = env.contract.address;
-
-    let transfer_from_msg = get_cw20_transfer_from_msg(
-        &config.token_addr,
-        &info.sender,
-        &contract_addrss,
-        //burn fee would be disappeared from user's wallet directly
-        gross - burn_fee,
-    )?;
-    resp = resp.add_message(transfer_from_msg);
-
-    Ok(resp)
+    Ok(Response::new()
+        .add_attribute("action", "set-strike-creator")
+        .add_attribute("creator", creator)
+        .add_attribute("allowed", allowed.to_string()))
 }
 
 fn execute_close_round(
@@ -339,8 +2646,12 @@ fn execute_close_round(
     match &maybe_live_round {
         Some(live_round) => {
             if now >= live_round.close_time {
-                let finished_round =
+                let mut finished_round =
                     compute_round_close(deps.as_ref(), live_round)?;
+                apply_winnings_fees(deps.storage, &mut finished_round)?;
+                apply_round_tie_policies(deps.storage, &finished_round)?;
+                settle_house_seed(deps.storage, &finished_round)?;
+                record_round_stats(deps.storage, &finished_round)?;
                 ROUNDS.save(
                     deps.storage,
                     live_round.id.u128(),
@@ -358,14 +2669,28 @@ fn execute_close_round(
                                 None => "everybody".to_string(),
                             },
                         ),
+                        ("tie_policy", finished_round.tie_policy.to_string()),
+                        ("fee_model", finished_round.fee_model.to_string()),
                     ],
                 ));
+                if let Some(bucket) = finished_round.winning_bucket {
+                    resp =
+                        resp.add_event(Event::new("hopers_bet").add_attribute(
+                            "winning_bucket",
+                            bucket.to_string(),
+                        ));
+                }
                 LIVE_ROUND.remove(deps.storage);
             }
         }
         None => {}
     }
 
+    /* Only the bidding round is seeded now, its open price still unknown */
+    if LIVE_ROUND.may_load(deps.storage)?.is_none() {
+        settle_house_epoch(deps.storage)?;
+    }
+
     /* Close the bidding round if it is finished
      * NOTE Don't allow two live rounds at the same time - wait for the other to close
      */
@@ -380,12 +2705,24 @@ fn execute_close_round(
         };
         let close_time =
             open_time.plus_seconds(config.next_round_seconds.u128() as u64);
+        /* Range rounds have no sides to seed */
+        let house_seed = if config.bucket_bounds_bps.is_empty() {
+            seed_house(deps.storage, &config)?
+        } else {
+            Uint128::zero()
+        };
 
         NEXT_ROUND.save(
             deps.storage,
             &NextRound {
-                bear_amount: Uint128::zero(),
-                bull_amount: Uint128::zero(),
+                bear_amount: house_seed,
+                bull_amount: house_seed,
+                fee_amount: Uint128::zero(),
+                bull_count: 0,
+                bear_count: 0,
+                house_seed,
+                fee_model: config.fee_model.clone(),
+                winnings_fee_rate: snapshot_winnings_fee_rate(&config),
                 bid_time: env.block.time,
                 close_time,
                 open_time,
@@ -393,6 +2730,13 @@ fn execute_close_round(
             },
         )?;
         NEXT_ROUND_ID.save(deps.storage, &(id.u128() + 1u128))?;
+        if !config.bucket_bounds_bps.is_empty() {
+            ROUND_BUCKETS.save(
+                deps.storage,
+                id.u128(),
+                &config.bucket_bounds_bps,
+            )?;
+        }
         Ok(id)
     };
     let maybe_open_round = NEXT_ROUND.may_load(deps.storage)?;
@@ -440,35 +2784,152 @@ fn execute_update_config(
 ) -> Result<Response, ContractError> {
     assert_is_admin(deps.as_ref(), info, env)?;
     let config = CONFIG.load(deps.as_ref().storage)?;
+    let prev_fee_staking = config.fee_staking.clone();
 
-    let next_round_seconds = u_config
-        .next_round_seconds
-        .unwrap_or(config.next_round_seconds);
-    let fast_oracle_addr =
-        u_config.fast_oracle_addr.unwrap_or(config.fast_oracle_addr);
+    let new_config = Config {
+        next_round_seconds: u_config
+            .next_round_seconds
+            .unwrap_or(config.next_round_seconds),
+        fast_oracle_addr: u_config
+            .fast_oracle_addr
+            .unwrap_or(config.fast_oracle_addr),
+        minimum_bet: u_config.minimum_bet.unwrap_or(config.minimum_bet),
+        burn_fee: u_config.burn_fee.unwrap_or(config.burn_fee),
+        gaming_fee: u_config.gaming_fee.unwrap_or(config.gaming_fee),
+        token_addr: u_config.token_addr.unwrap_or(config.token_addr),
+        tie_policy: u_config.tie_policy.unwrap_or(config.tie_policy),
+        tie_band_bps: u_config.tie_band_bps.unwrap_or(config.tie_band_bps),
+        withdrawal_penalty: u_config
+            .withdrawal_penalty
+            .unwrap_or(config.withdrawal_penalty),
+        stake_denom: u_config.stake_denom.unwrap_or(config.stake_denom),
+        push_settlement: u_config
+            .push_settlement
+            .unwrap_or(config.push_settlement),
+        position_nft_addr: u_config
+            .position_nft_addr
+            .unwrap_or(config.position_nft_addr),
+        bucket_bounds_bps: u_config
+            .bucket_bounds_bps
+            .unwrap_or(config.bucket_bounds_bps),
+        referral_share: u_config
+            .referral_share
+            .unwrap_or(config.referral_share),
+        fee_staking: u_config.fee_staking.unwrap_or(config.fee_staking),
+        staking_share: u_config.staking_share.unwrap_or(config.staking_share),
+        staking_lock_seconds: u_config
+            .staking_lock_seconds
+            .unwrap_or(config.staking_lock_seconds),
+        house_seed_cap: u_config
+            .house_seed_cap
+            .unwrap_or(config.house_seed_cap),
+        house_fee_share: u_config
+            .house_fee_share
+            .unwrap_or(config.house_fee_share),
+        fee_model: u_config.fee_model.unwrap_or(config.fee_model),
+        maximum_bet: u_config.maximum_bet.unwrap_or(config.maximum_bet),
+        maximum_position: u_config
+            .maximum_position
+            .unwrap_or(config.maximum_position),
+        maximum_round_pool: u_config
+            .maximum_round_pool
+            .unwrap_or(config.maximum_round_pool),
+    };
+    validate_config(deps.as_ref(), &new_config)?;
 
-    let minimum_bet = u_config.minimum_bet.unwrap_or(config.minimum_bet);
-    let burn_fee = u_config.burn_fee.unwrap_or(config.burn_fee);
-    let gaming_fee = u_config.gaming_fee.unwrap_or(config.gaming_fee);
-    let token_addr = u_config.token_addr.unwrap_or(config.token_addr);
+    /* Stakers are paid back in the staking token */
+    if new_config.fee_staking != prev_fee_staking
+        && matches!(prev_fee_staking, FeeStaking::Internal { .. })
+        && !STAKING_STATE
+            .may_load(deps.storage)?
+            .unwrap_or_default()
+            .total_staked
+            .is_zero()
+    {
+        return Err(ContractError::Std(StdError::generic_err(
+            "Internal staking can't change while tokens are staked",
+        )));
+    }
 
-    CONFIG.save(
-        deps.storage,
-        &Config {
-            next_round_seconds,
-            fast_oracle_addr,
-            minimum_bet,
-            burn_fee,
-            gaming_fee,
-            token_addr,
-        },
-    )?;
+    CONFIG.save(deps.storage, &new_config)?;
 
     Ok(Response::new())
 }
 
+/**
+ * Checks a whole config, as instantiated or as updated
+ */
+fn validate_config(deps: Deps, config: &Config) -> Result<(), ContractError> {
+    deps.api.addr_validate(config.fast_oracle_addr.as_str())?;
+    deps.api.addr_validate(config.token_addr.as_str())?;
+    if let Some(nft_addr) = &config.position_nft_addr {
+        deps.api.addr_validate(nft_addr.as_str())?;
+    }
+    match &config.fee_staking {
+        FeeStaking::Contract { contract_addr } => {
+            deps.api.addr_validate(contract_addr.as_str())?;
+        }
+        FeeStaking::Internal { token_addr } => {
+            deps.api.addr_validate(token_addr.as_str())?;
+        }
+        FeeStaking::Disabled => {}
+    }
+
+    if config.next_round_seconds.is_zero() {
+        return Err(ContractError::ZeroRoundDuration {});
+    }
+
+    let full = Uint128::new(FEE_PRECISION * 100);
+    if config.burn_fee + config.gaming_fee > full {
+        return Err(ContractError::FeesTooHigh {});
+    }
+    for (name, share) in [
+        ("withdrawal_penalty", config.withdrawal_penalty),
+        ("referral_share", config.referral_share),
+        (
+            "staking_share + house_fee_share",
+            config.staking_share + config.house_fee_share,
+        ),
+    ] {
+        if share > full {
+            return Err(ContractError::ShareTooHigh {
+                name: name.to_string(),
+            });
+        }
+    }
+    if config.tie_band_bps > Uint128::new(BPS_PRECISION) {
+        return Err(ContractError::ShareTooHigh {
+            name: "tie_band_bps".to_string(),
+        });
+    }
+
+    if let Some(denom) = &config.stake_denom {
+        assert_valid_denom(denom)?;
+    }
+    assert_valid_bet_limits(&config.stake_token())?;
+
+    assert_bucket_bounds(&config.bucket_bounds_bps)?;
+    Ok(())
+}
+
+/**
+ * Follows the sdk's denom format, which also fits token factory denoms
+ */
+fn assert_valid_denom(denom: &str) -> Result<(), ContractError> {
+    let mut chars = denom.chars();
+    let valid = (3..=128).contains(&denom.len())
+        && chars.next().map_or(false, |c| c.is_ascii_alphabetic())
+        && chars.all(|c| c.is_ascii_alphanumeric() || "/:._-".contains(c));
+    if !valid {
+        return Err(ContractError::InvalidDenom {
+            denom: denom.to_string(),
+        });
+    }
+    Ok(())
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::Config {} => to_binary(&query_config(deps)?),
         QueryMsg::Status {} => to_binary(&query_status(deps)?),
@@ -478,23 +2939,639 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
         QueryMsg::FinishedRound { round_id } => {
             to_binary(&query_finished_round(deps, round_id)?)
         }
+        QueryMsg::FinishedRounds {
+            start_after,
+            limit,
+            order,
+        } => {
+            to_binary(&query_finished_rounds(deps, start_after, limit, order)?)
+        }
+        QueryMsg::RoundAtTime { timestamp } => {
+            to_binary(&query_round_at_time(deps, timestamp)?)
+        }
+        QueryMsg::RoundStats {} => {
+            to_binary(&ROUND_STATS.may_load(deps.storage)?.unwrap_or_default())
+        }
+        QueryMsg::GlobalStats {} => {
+            to_binary(&GLOBAL_STATS.may_load(deps.storage)?.unwrap_or_default())
+        }
+        QueryMsg::PlayerStats { player } => {
+            to_binary(&query_player_stats(deps, player)?)
+        }
+        QueryMsg::Leaderboard {
+            order_by,
+            start_after,
+            limit,
+        } => to_binary(&query_leaderboard(deps, order_by, start_after, limit)?),
         QueryMsg::MyGameList {
             player,
             start_after,
             limit,
         } => to_binary(&query_my_games(deps, player, start_after, limit)?),
+        QueryMsg::RoundBets {
+            round_id,
+            direction,
+            start_after,
+            limit,
+        } => to_binary(&query_round_bets(
+            deps,
+            round_id,
+            direction,
+            start_after,
+            limit,
+        )?),
         QueryMsg::MyPendingReward { player } => {
             to_binary(&query_my_pending_reward(deps, player)?)
         }
+        QueryMsg::ClaimOperators { player } => {
+            to_binary(&query_claim_operators(deps, player)?)
+        }
+        QueryMsg::ReferralEarnings { referrer } => {
+            to_binary(&query_referral_earnings(deps, referrer)?)
+        }
+        QueryMsg::ReferredPlayers {
+            referrer,
+            start_after,
+            limit,
+        } => to_binary(&query_referred_players(
+            deps,
+            referrer,
+            start_after,
+            limit,
+        )?),
+        QueryMsg::StakeTokens { start_after, limit } => {
+            to_binary(&query_stake_tokens(deps, start_after, limit)?)
+        }
+        QueryMsg::FeeTiers {} => to_binary(&FeeTiersResponse {
+            tiers: FEE_TIERS.may_load(deps.storage)?.unwrap_or_default(),
+        }),
+        QueryMsg::StakingState {} => to_binary(
+            &STAKING_STATE.may_load(deps.storage)?.unwrap_or_default(),
+        ),
+        QueryMsg::Staker { address } => {
+            let staker = deps.api.addr_validate(&address)?;
+            let staker_info = load_staker(deps.storage, &staker)?;
+            to_binary(&StakerResponse {
+                amount: staker_info.amount,
+                pending_reward: staker_info.pending_reward,
+                locked_until: staker_info.locked_until,
+            })
+        }
+        QueryMsg::HousePool {} => to_binary(&query_house_pool(deps)?),
+        QueryMsg::HouseShares { address } => {
+            let depositor = deps.api.addr_validate(&address)?;
+            let pool = HOUSE_POOL.may_load(deps.storage)?.unwrap_or_default();
+            let (request, minted) =
+                load_house_request(deps.storage, &pool, &depositor)?;
+            let shares = HOUSE_SHARES
+                .may_load(deps.storage, &depositor)?
+                .unwrap_or_default()
+                + minted;
+            to_binary(&HouseSharesResponse {
+                shares,
+                value: compute_house_share_value(&pool, shares),
+                pending_deposit: request.deposit,
+                pending_shares: request.shares,
+                claimable: request.claimable,
+            })
+        }
+        QueryMsg::FeeRecipients {} => to_binary(&FeeRecipientsResponse {
+            recipients: FEE_RECIPIENTS
+                .may_load(deps.storage)?
+                .unwrap_or_default(),
+        }),
+        QueryMsg::FeeDistributions { start_after, limit } => {
+            to_binary(&query_fee_distributions(deps, start_after, limit)?)
+        }
+        QueryMsg::PlayerFeeTier { player } => {
+            to_binary(&query_player_fee_tier(deps, env, player)?)
+        }
+        QueryMsg::Odds { round_id } => to_binary(&query_odds(deps, round_id)?),
+        QueryMsg::SimulateBet {
+            round_id,
+            direction,
+            gross,
+            player,
+        } => to_binary(&query_simulate_bet(
+            deps, env, round_id, direction, gross, player,
+        )?),
+        QueryMsg::StrikeMarket { market_id } => {
+            to_binary(&STRIKE_MARKETS.load(deps.storage, market_id.u128())?)
+        }
+        QueryMsg::StrikeMarkets { start_after, limit } => {
+            to_binary(&query_strike_markets(deps, start_after, limit)?)
+        }
+        QueryMsg::RoundBuckets { round_id } => {
+            to_binary(&query_round_buckets(deps, round_id)?)
+        }
+        QueryMsg::RoundPools { round_id } => {
+            to_binary(&query_round_pools(deps, round_id)?)
+        }
+    }
+}
+
+fn query_finished_round(
+    deps: Deps,
+    round_id: Uint128,
+) -> StdResult<FinishedRound> {
+    let round = ROUNDS.load(deps.storage, round_id.u128())?;
+    Ok(round)
+}
+
+fn query_finished_rounds(
+    deps: Deps,
+    start_after: Option<Uint128>,
+    limit: Option<u32>,
+    order: Option<OrderBy>,
+) -> StdResult<FinishedRoundsResponse> {
+    let limit =
+        limit.unwrap_or(DEFAULT_QUERY_LIMIT).min(MAX_QUERY_LIMIT) as usize;
+    let start_after = start_after.map(|id| Bound::exclusive(id.u128()));
+    /* Strike markets are numbered past the rounds */
+    let strike_markets = Some(Bound::exclusive(STRIKE_MARKET_ID_OFFSET));
+
+    let (min, max, order) = match order {
+        Some(OrderBy::Desc) => {
+            (None, start_after.or(strike_markets), Order::Descending)
+        }
+        _ => (start_after, strike_markets, Order::Ascending),
+    };
+
+    let rounds = ROUNDS
+        .range(deps.storage, min, max, order)
+        .take(limit)
+        .map(|res| res.map(|item| item.1))
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(FinishedRoundsResponse { rounds })
+}
+
+/**
+ * Rounds close in id order, so the first one closing after `timestamp` is
+ * found by bisecting the ids
+ */
+fn query_round_at_time(
+    deps: Deps,
+    timestamp: Timestamp,
+) -> StdResult<RoundAtTimeResponse> {
+    let (mut low, mut high) = (0u128, NEXT_ROUND_ID.load(deps.storage)?);
+    while low < high {
+        let mid = low + (high - low) / 2;
+        /* Rounds that haven't finished close after any finished one */
+        match ROUNDS.may_load(deps.storage, mid)? {
+            Some(round) if round.close_time <= timestamp => low = mid + 1,
+            _ => high = mid,
+        }
+    }
+
+    let round = ROUNDS
+        .may_load(deps.storage, low)?
+        .filter(|round| round.open_time <= timestamp);
+    Ok(RoundAtTimeResponse { round })
+}
+
+fn query_player_stats(deps: Deps, player: String) -> StdResult<PlayerStats> {
+    let player = deps.api.addr_validate(&player)?;
+    Ok(player_stats_storage()
+        .may_load(deps.storage, player)?
+        .unwrap_or_default())
+}
+
+fn query_leaderboard(
+    deps: Deps,
+    order_by: LeaderboardOrder,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<LeaderboardResponse> {
+    let limit =
+        limit.unwrap_or(DEFAULT_QUERY_LIMIT).min(MAX_QUERY_LIMIT) as usize;
+    let start = match start_after {
+        Some(player) => {
+            let player = deps.api.addr_validate(&player)?;
+            let stats =
+                player_stats_storage().load(deps.storage, player.clone())?;
+            Some((player, stats))
+        }
+        None => None,
+    };
+
+    let players = match order_by {
+        LeaderboardOrder::Pnl => player_stats_storage()
+            .idx
+            .pnl
+            .range(
+                deps.storage,
+                None,
+                start.map(|(player, stats)| {
+                    Bound::exclusive((stats.net_pnl, player))
+                }),
+                Order::Descending,
+            )
+            .take(limit)
+            .collect::<StdResult<Vec<_>>>()?,
+        LeaderboardOrder::Volume => player_stats_storage()
+            .idx
+            .volume
+            .range(
+                deps.storage,
+                None,
+                start.map(|(player, stats)| {
+                    Bound::exclusive((stats.total_staked.u128(), player))
+                }),
+                Order::Descending,
+            )
+            .take(limit)
+            .collect::<StdResult<Vec<_>>>()?,
+    };
+
+    Ok(LeaderboardResponse {
+        entries: players
+            .into_iter()
+            .map(|(player, stats)| LeaderboardEntry { player, stats })
+            .collect(),
+    })
+}
+
+fn query_claim_operators(
+    deps: Deps,
+    player: String,
+) -> StdResult<ClaimOperatorsResponse> {
+    let player = deps.api.addr_validate(&player)?;
+
+    let operators = CLAIM_OPERATORS
+        .prefix(&player)
+        .keys(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(ClaimOperatorsResponse { operators })
+}
+
+fn query_referral_earnings(
+    deps: Deps,
+    referrer: String,
+) -> StdResult<ReferralEarningsResponse> {
+    let referrer = deps.api.addr_validate(&referrer)?;
+
+    let earnings = REFERRAL_EARNINGS
+        .prefix(&referrer)
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|res| res.map(|item| item.1))
+        .collect::<StdResult<Vec<_>>>()?;
+    let claimable = REFERRAL_CLAIMABLE
+        .prefix(&referrer)
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|res| res.map(|item| item.1))
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(ReferralEarningsResponse {
+        earnings,
+        claimable,
+    })
+}
+
+fn query_referred_players(
+    deps: Deps,
+    referrer: String,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<ReferredPlayersResponse> {
+    let referrer = deps.api.addr_validate(&referrer)?;
+    let limit =
+        limit.unwrap_or(DEFAULT_QUERY_LIMIT).min(MAX_QUERY_LIMIT) as usize;
+    let start_after = start_after
+        .map(|player| deps.api.addr_validate(&player))
+        .transpose()?;
+
+    let players = REFERRALS
+        .prefix(&referrer)
+        .keys(
+            deps.storage,
+            start_after.as_ref().map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(limit)
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(ReferredPlayersResponse { players })
+}
+
+fn query_house_pool(deps: Deps) -> StdResult<HousePoolResponse> {
+    let pool = HOUSE_POOL.may_load(deps.storage)?.unwrap_or_default();
+    let share_price = if pool.total_shares.is_zero() {
+        Decimal::one()
+    } else {
+        Decimal::from_ratio(pool.nav(), pool.total_shares)
+    };
+
+    Ok(HousePoolResponse {
+        nav: pool.nav(),
+        share_price,
+        pool,
+    })
+}
+
+fn compute_house_share_value(pool: &HousePool, shares: Uint128) -> Uint128 {
+    if pool.total_shares.is_zero() {
+        return Uint128::zero();
+    }
+    pool.nav().multiply_ratio(shares, pool.total_shares)
+}
+
+fn query_fee_distributions(
+    deps: Deps,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<FeeDistributionsResponse> {
+    let limit =
+        limit.unwrap_or(DEFAULT_QUERY_LIMIT).min(MAX_QUERY_LIMIT) as usize;
+
+    let distributions = FEE_DISTRIBUTIONS
+        .range(
+            deps.storage,
+            start_after.map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(limit)
+        .map(|res| res.map(|item| item.1))
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(FeeDistributionsResponse { distributions })
+}
+
+fn query_player_fee_tier(
+    deps: Deps,
+    env: Env,
+    player: String,
+) -> StdResult<PlayerFeeTierResponse> {
+    let player = deps.api.addr_validate(&player)?;
+
+    Ok(PlayerFeeTierResponse {
+        tier: load_fee_tier(deps, &env, &player)?,
+        trailing_volume: query_trailing_volume(deps.storage, &env, &player)?,
+    })
+}
+
+fn query_stake_tokens(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<StakeTokensResponse> {
+    let limit =
+        limit.unwrap_or(DEFAULT_QUERY_LIMIT).min(MAX_QUERY_LIMIT) as usize;
+    let start = start_after.as_deref().map(Bound::exclusive);
+
+    let tokens = STAKE_TOKENS
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|res| res.map(|item| item.1))
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(StakeTokensResponse { tokens })
+}
+
+fn query_odds(deps: Deps, round_id: Uint128) -> StdResult<OddsResponse> {
+    let pool = load_round_pool(deps, round_id)?;
+    let fee_model = load_round_fee_model(deps, round_id)?;
+    let winnings_fee =
+        estimate_winnings_fee(deps, round_id, &fee_model, &pool)?;
+
+    Ok(OddsResponse {
+        bull_multiplier: compute_multiplier(
+            &pool,
+            &Direction::Bull,
+            winnings_fee,
+        ),
+        bear_multiplier: compute_multiplier(
+            &pool,
+            &Direction::Bear,
+            winnings_fee,
+        ),
+    })
+}
+
+fn query_simulate_bet(
+    deps: Deps,
+    env: Env,
+    round_id: Uint128,
+    direction: Direction,
+    gross: Uint128,
+    player: Option<String>,
+) -> StdResult<SimulateBetResponse> {
+    let stake = load_stake_token(deps, &None)?;
+    let player = player
+        .map(|player| deps.api.addr_validate(&player))
+        .transpose()?;
+    let discount = match &player {
+        Some(player) => load_fee_tier(deps, &env, player)?
+            .map(|tier| tier.discount)
+            .unwrap_or_default(),
+        None => Uint128::zero(),
+    };
+    let fee_model = load_round_fee_model(deps, round_id)?;
+    let (burn_fee, gaming_fee) = match fee_model {
+        FeeModel::OnStake => (
+            compute_burn_fee(&stake, gross, discount)?,
+            compute_gaming_fee(&stake, gross, discount)?,
+        ),
+        FeeModel::OnWinnings => (Uint128::zero(), Uint128::zero()),
+    };
+    let net_stake = gross.saturating_sub(burn_fee + gaming_fee);
+
+    /* The same checks `execute_bet` makes before taking the stake */
+    let check_bet = || -> Result<(), ContractError> {
+        assert_not_haulted(deps)?;
+        let round = load_bidding_round(deps, round_id)?;
+        if ROUND_BUCKETS.has(deps.storage, round_id.u128()) {
+            return Err(ContractError::Std(StdError::generic_err(format!(
+                "Round {} is a range round, bet on a bucket",
+                round_id
+            ))));
+        }
+        assert_round_takes_bets(&env, &round)?;
+        assert_minimum_bet(&stake, gross)?;
+        let mut position = net_stake;
+        if let Some(player) = &player {
+            let bet_info = bet_info_storage().may_load(
+                deps.storage,
+                bet_info_key(round_id.u128(), player),
+            )?;
+            if let Some(bet_info) = bet_info {
+                assert_can_increase_bet(&bet_info, &direction, &None, &None)?;
+                position += bet_info.amount;
+            }
+        }
+        assert_bet_limits(
+            &stake,
+            gross,
+            position,
+            compute_players_round_total(deps.storage, &round, &None)?
+                + net_stake,
+        )
+    };
+    let error = check_bet().err().map(|err| err.to_string());
+
+    let mut pool = load_round_pool(deps, round_id)?;
+    if error.is_none() {
+        *pool.side_amount_mut(&direction) += net_stake;
+    }
+    let winnings_fee =
+        estimate_winnings_fee(deps, round_id, &fee_model, &pool)?;
+    let multiplier = compute_multiplier(&pool, &direction, winnings_fee);
+
+    Ok(SimulateBetResponse {
+        burn_fee,
+        gaming_fee,
+        net_stake,
+        bull_multiplier: compute_multiplier(
+            &pool,
+            &Direction::Bull,
+            winnings_fee,
+        ),
+        bear_multiplier: compute_multiplier(
+            &pool,
+            &Direction::Bear,
+            winnings_fee,
+        ),
+        payout_if_win: match (&error, multiplier) {
+            (None, Some(multiplier)) => net_stake * multiplier,
+            _ => Uint128::zero(),
+        },
+        error,
+    })
+}
+
+/**
+ * Pool of the market's own stake in a round at any stage
+ */
+fn load_round_pool(deps: Deps, round_id: Uint128) -> StdResult<RoundPool> {
+    if let Some(round) = ROUNDS.may_load(deps.storage, round_id.u128())? {
+        return load_finished_round_pool(deps.storage, &round, &None);
+    }
+    if let Some(round) = LIVE_ROUND
+        .may_load(deps.storage)?
+        .filter(|round| round.id == round_id)
+    {
+        return Ok(RoundPool {
+            bull_amount: round.bull_amount,
+            bear_amount: round.bear_amount,
+            fee_amount: round.fee_amount,
+        });
+    }
+
+    let round = load_bidding_round(deps, round_id)?;
+    load_next_round_pool(deps.storage, &round, &None)
+}
+
+fn load_round_fee_model(deps: Deps, round_id: Uint128) -> StdResult<FeeModel> {
+    if let Some(round) = ROUNDS.may_load(deps.storage, round_id.u128())? {
+        return Ok(round.fee_model);
+    }
+    if let Some(round) = LIVE_ROUND
+        .may_load(deps.storage)?
+        .filter(|round| round.id == round_id)
+    {
+        return Ok(round.fee_model);
+    }
+
+    Ok(load_bidding_round(deps, round_id)?.fee_model)
+}
+
+/**
+ * House seed and winnings fee rate of a round that isn't finished
+ */
+fn load_round_seed_and_fee_rate(
+    deps: Deps,
+    round_id: Uint128,
+) -> StdResult<(Uint128, Option<Uint128>)> {
+    if let Some(round) = LIVE_ROUND
+        .may_load(deps.storage)?
+        .filter(|round| round.id == round_id)
+    {
+        return Ok((round.house_seed, round.winnings_fee_rate));
+    }
+
+    let round = load_bidding_round(deps, round_id)?;
+    Ok((round.house_seed, round.winnings_fee_rate))
+}
+
+/**
+ * Fee a pool pays if it's won: the one taken for finished rounds, the
+ * current rate on the pool so far before that
+ */
+fn estimate_winnings_fee(
+    deps: Deps,
+    round_id: Uint128,
+    fee_model: &FeeModel,
+    pool: &RoundPool,
+) -> StdResult<Uint128> {
+    match fee_model {
+        FeeModel::OnStake => Ok(Uint128::zero()),
+        FeeModel::OnWinnings if ROUNDS.has(deps.storage, round_id.u128()) => {
+            Ok(pool.fee_amount)
+        }
+        FeeModel::OnWinnings => {
+            let (house_seed, fee_rate) =
+                load_round_seed_and_fee_rate(deps, round_id)?;
+            compute_winnings_fee(
+                resolve_winnings_fee_rate(deps.storage, fee_rate)?,
+                pool.total() - house_seed - house_seed,
+            )
+        }
+    }
+}
+
+fn compute_multiplier(
+    pool: &RoundPool,
+    dir: &Direction,
+    winnings_fee: Uint128,
+) -> Option<Decimal> {
+    let side_amount = pool.side_amount(dir);
+    if side_amount.is_zero() {
+        return None;
+    }
+    Some(Decimal::from_ratio(
+        pool.total() - winnings_fee,
+        side_amount,
+    ))
+}
+
+fn query_strike_markets(
+    deps: Deps,
+    start_after: Option<Uint128>,
+    limit: Option<u32>,
+) -> StdResult<StrikeMarketsResponse> {
+    let limit =
+        limit.unwrap_or(DEFAULT_QUERY_LIMIT).min(MAX_QUERY_LIMIT) as usize;
+    let start = start_after.map(|id| Bound::exclusive(id.u128()));
+
+    let markets = STRIKE_MARKETS
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|res| res.map(|item| item.1))
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(StrikeMarketsResponse { markets })
+}
+
+fn query_round_buckets(
+    deps: Deps,
+    round_id: Uint128,
+) -> StdResult<RoundBucketsResponse> {
+    let bounds_bps = ROUND_BUCKETS
+        .may_load(deps.storage, round_id.u128())?
+        .unwrap_or_default();
+
+    let mut pools = vec![Uint128::zero(); bounds_bps.len() + 1];
+    for (bucket, amount) in load_bucket_pools(deps.storage, round_id)? {
+        pools[bucket as usize] = amount;
     }
+    Ok(RoundBucketsResponse { bounds_bps, pools })
 }
 
-fn query_finished_round(
+fn query_round_pools(
     deps: Deps,
     round_id: Uint128,
-) -> StdResult<FinishedRound> {
-    let round = ROUNDS.load(deps.storage, round_id.u128())?;
-    Ok(round)
+) -> StdResult<RoundPoolsResponse> {
+    let pools = ROUND_POOLS
+        .prefix(round_id.u128())
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(RoundPoolsResponse { pools })
 }
 
 fn query_my_current_position(
@@ -590,66 +3667,141 @@ pub fn query_my_games(
     Ok(MyGameResponse { my_game_list })
 }
 
+fn query_round_bets(
+    deps: Deps,
+    round_id: Uint128,
+    direction: Option<Direction>,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<RoundBetsResponse> {
+    let limit =
+        limit.unwrap_or(DEFAULT_QUERY_LIMIT).min(MAX_QUERY_LIMIT) as usize;
+    let start = start_after
+        .map(|player| deps.api.addr_validate(&player))
+        .transpose()?;
+    let filter = |res: &StdResult<(Addr, BetInfo)>| match (res, &direction) {
+        (Ok((_, bet_info)), Some(direction)) => {
+            bet_info.direction == *direction
+        }
+        _ => true,
+    };
+
+    /* A player's bet is either still open or settled, never both */
+    let mut bets = bet_info_storage()
+        .prefix(round_id.u128())
+        .range(
+            deps.storage,
+            start.clone().map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .filter(filter)
+        .take(limit)
+        .collect::<StdResult<Vec<_>>>()?;
+    bets.extend(
+        SETTLED_BETS
+            .prefix(round_id.u128())
+            .range(
+                deps.storage,
+                start.as_ref().map(Bound::exclusive),
+                None,
+                Order::Ascending,
+            )
+            .filter(filter)
+            .take(limit)
+            .collect::<StdResult<Vec<_>>>()?,
+    );
+    bets.sort_by(|(player_a, _), (player_b, _)| player_a.cmp(player_b));
+    bets.truncate(limit);
+
+    Ok(RoundBetsResponse {
+        bets: bets.into_iter().map(|(_, bet_info)| bet_info).collect(),
+    })
+}
+
 pub fn query_my_pending_reward(
     deps: Deps,
     player: Addr,
 ) -> StdResult<PendingRewardResponse> {
-    let my_game_list = query_my_games_without_limit(deps, player.clone())?;
-    let mut winnings = Uint128::zero();
-
-    for game in my_game_list.my_game_list {
-        let round_id = game.round_id;
-        let round = ROUNDS.may_load(deps.storage, round_id.u128())?;
+    let stake_asset = CONFIG.load(deps.storage)?.stake_asset();
+    /* One entry per token; bets join the ledger as they're settled */
+    let rewards: Vec<AssetAmount> = CLAIMABLE
+        .prefix(&player)
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|res| res.map(|item| item.1))
+        .collect::<StdResult<Vec<_>>>()?;
 
-        if round.is_none() {
-            continue;
+    let mut winnings = Uint128::zero();
+    let mut token_rewards: Vec<AssetAmount> = vec![];
+    for reward in rewards {
+        if reward.info == stake_asset {
+            winnings += reward.amount;
+        } else {
+            token_rewards.push(reward);
         }
-        let round = round.unwrap();
+    }
+
+    Ok(PendingRewardResponse {
+        pending_reward: winnings,
+        token_rewards,
+    })
+}
 
-        let pool_shares = round.bear_amount + round.bull_amount;
+/**
+ * Bets of a player whose rounds have finished, oldest first; bets in the
+ * bidding or live round are left out
+ */
+fn query_my_settled_games(
+    deps: Deps,
+    player: Addr,
+    round_ids: Option<Vec<Uint128>>,
+    limit: usize,
+) -> StdResult<Vec<(BetInfo, FinishedRound)>> {
+    /*
+     * Tokenized bets belong to whoever holds their position token; the
+     * player index already leaves them out
+     */
+    let settled =
+        |game: BetInfo| -> StdResult<Option<(BetInfo, FinishedRound)>> {
+            if game.position_nft.is_some() {
+                return Ok(None);
+            }
+            Ok(ROUNDS
+                .may_load(deps.storage, game.round_id.u128())?
+                .map(|round| (game, round)))
+        };
 
-        if round.bear_amount == Uint128::zero()
-            || round.bull_amount == Uint128::zero()
-        {
-            winnings += game.amount;
-        } else {
-            let round_winnings = match round.winner {
-                Some(Direction::Bull) => {
-                    /* Only claimable once */
-                    match game.direction {
-                        Direction::Bull => {
-                            let won_shares = game.amount;
-                            pool_shares
-                                .multiply_ratio(won_shares, round.bull_amount)
-                        }
-                        Direction::Bear => Uint128::zero(),
-                    }
-                }
-                Some(Direction::Bear) => {
-                    /* Only claimable once */
-                    match game.direction {
-                        Direction::Bull => Uint128::zero(),
-                        Direction::Bear => {
-                            let won_shares = game.amount;
-                            pool_shares
-                                .multiply_ratio(won_shares, round.bear_amount)
-                        }
+    match round_ids {
+        Some(mut round_ids) => {
+            round_ids.sort();
+            round_ids.dedup();
+            let mut games = vec![];
+            for round_id in round_ids {
+                let game = bet_info_storage().may_load(
+                    deps.storage,
+                    bet_info_key(round_id.u128(), &player),
+                )?;
+                if let Some(game) = game {
+                    if let Some(settled_game) = settled(game)? {
+                        games.push(settled_game);
                     }
                 }
-                None => {
-                    /* Only claimable once */
-                    game.amount
+                if games.len() == limit {
+                    break;
                 }
-            };
-
-            /* Count it up */
-            winnings += round_winnings;
+            }
+            Ok(games)
         }
+        None => bet_info_storage()
+            .idx
+            .player
+            .prefix(player)
+            .range(deps.storage, None, None, Order::Ascending)
+            .map(|res| res.and_then(|item| settled(item.1)))
+            .filter_map(|res| res.transpose())
+            .take(limit)
+            .collect(),
     }
-
-    Ok(PendingRewardResponse {
-        pending_reward: winnings,
-    })
 }
 
 pub fn query_my_games_without_limit(
@@ -666,6 +3818,22 @@ pub fn query_my_games_without_limit(
     Ok(MyGameResponse { my_game_list })
 }
 
+/**
+ * The round a bet is placed in: the bidding round or a strike market
+ */
+fn load_bidding_round(deps: Deps, round_id: Uint128) -> StdResult<NextRound> {
+    if round_id.u128() < STRIKE_MARKET_ID_OFFSET {
+        return assert_is_current_round(deps, round_id);
+    }
+
+    let market = STRIKE_MARKETS
+        .may_load(deps.storage, round_id.u128())?
+        .ok_or_else(|| {
+            StdError::generic_err(format!("No strike market {}", round_id))
+        })?;
+    Ok(market.bidding_round())
+}
+
 fn assert_is_current_round(
     deps: Deps,
     round_id: Uint128,
@@ -682,19 +3850,84 @@ fn assert_is_current_round(
     Ok(open_round)
 }
 
-fn compute_burn_fee(deps: Deps, gross: Uint128) -> StdResult<Uint128> {
-    let burn_fee = CONFIG.load(deps.storage)?.burn_fee;
+/**
+ * Native stakes are taken from the attached coins, cw20 stakes are pulled
+ * with an allowance and must not be sent coins
+ */
+fn assert_stake_funds(
+    asset: &AssetInfo,
+    info: &MessageInfo,
+    gross: Uint128,
+) -> StdResult<()> {
+    match asset {
+        AssetInfo::NativeToken { denom } => {
+            if info.funds.len() != 1
+                || info.funds[0].denom != *denom
+                || info.funds[0].amount != gross
+            {
+                return Err(StdError::generic_err(format!(
+                    "Send exactly {}{} and no other coins",
+                    gross, denom
+                )));
+            }
+        }
+        AssetInfo::Token { .. } => {
+            if !info.funds.is_empty() {
+                return Err(StdError::generic_err(
+                    "Bets in a cw20 token don't accept native coins",
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+fn compute_burn_fee(
+    stake: &StakeToken,
+    gross: Uint128,
+    discount: Uint128,
+) -> StdResult<Uint128> {
+    apply_fee_discount(stake.burn_fee, discount)
+        .checked_multiply_ratio(gross, FEE_PRECISION * 100)
+        .map_err(|e| StdError::generic_err(e.to_string()))
+}
 
-    burn_fee
+fn compute_gaming_fee(
+    stake: &StakeToken,
+    gross: Uint128,
+    discount: Uint128,
+) -> StdResult<Uint128> {
+    apply_fee_discount(stake.gaming_fee, discount)
         .checked_multiply_ratio(gross, FEE_PRECISION * 100)
         .map_err(|e| StdError::generic_err(e.to_string()))
 }
 
-fn compute_gaming_fee(deps: Deps, gross: Uint128) -> StdResult<Uint128> {
-    let staker_fee = CONFIG.load(deps.storage)?.gaming_fee;
+/**
+ * A fee rate less the share a fee tier waives
+ */
+fn apply_fee_discount(fee: Uint128, discount: Uint128) -> Uint128 {
+    let full = Uint128::new(FEE_PRECISION * 100);
+    fee.multiply_ratio(full.saturating_sub(discount), full)
+}
+
+fn compute_referral_fee(
+    config: &Config,
+    gaming_fee: Uint128,
+) -> StdResult<Uint128> {
+    config
+        .referral_share
+        .checked_multiply_ratio(gaming_fee, FEE_PRECISION * 100)
+        .map_err(|e| StdError::generic_err(e.to_string()))
+}
+
+fn compute_withdrawal_penalty(
+    deps: Deps,
+    amount: Uint128,
+) -> StdResult<Uint128> {
+    let withdrawal_penalty = CONFIG.load(deps.storage)?.withdrawal_penalty;
 
-    staker_fee
-        .checked_multiply_ratio(gross, FEE_PRECISION * 100)
+    withdrawal_penalty
+        .checked_multiply_ratio(amount, FEE_PRECISION * 100)
         .map_err(|e| StdError::generic_err(e.to_string()))
 }
 
@@ -718,9 +3951,40 @@ fn compute_round_open(
         open_price,
         bull_amount: round.bull_amount,
         bear_amount: round.bear_amount,
+        fee_amount: round.fee_amount,
+        bull_count: round.bull_count,
+        bear_count: round.bear_count,
+        house_seed: round.house_seed,
+        fee_model: round.fee_model.clone(),
+        winnings_fee_rate: round.winnings_fee_rate,
     })
 }
 
+fn position_token_id(round_id: Uint128, player: &Addr) -> String {
+    format!("{}-{}", round_id, player)
+}
+
+/**
+ * The round and player of a position token, see `position_token_id`
+ */
+fn parse_position_token_id(
+    api: &dyn Api,
+    token_id: &str,
+) -> StdResult<(Uint128, Addr)> {
+    let parsed = token_id.split_once('-').and_then(|(round_id, player)| {
+        Some((round_id.parse::<u128>().ok()?, player))
+    });
+    match parsed {
+        Some((round_id, player)) => {
+            Ok((Uint128::new(round_id), api.addr_validate(player)?))
+        }
+        None => Err(StdError::generic_err(format!(
+            "{} isn't a position token",
+            token_id
+        ))),
+    }
+}
+
 fn get_current_price(deps: Deps) -> StdResult<Uint128> {
     let config = CONFIG.load(deps.storage)?;
 
@@ -738,23 +4002,31 @@ fn compute_round_close(
     round: &LiveRound,
 ) -> StdResult<FinishedRound> {
     let close_price = get_current_price(deps)?;
+    let config = CONFIG.load(deps.storage)?;
+
+    /* Moves inside the band are too small to call */
+    let band = round
+        .open_price
+        .multiply_ratio(config.tie_band_bps, BPS_PRECISION);
+
+    let bucket_bounds =
+        ROUND_BUCKETS.may_load(deps.storage, round.id.u128())?;
+    let winning_bucket = bucket_bounds
+        .as_ref()
+        .map(|bounds| compute_bucket(bounds, round.open_price, close_price));
 
-    let winner = match close_price.cmp(&round.open_price) {
-        std::cmp::Ordering::Greater =>
+    let winner = if winning_bucket.is_some() {
+        /* Range rounds are won by a bucket */
+        None
+    } else if close_price > round.open_price + band {
         /* Bulls win */
-        {
-            Some(Direction::Bull)
-        }
-        std::cmp::Ordering::Less =>
+        Some(Direction::Bull)
+    } else if close_price + band < round.open_price {
         /* Bears win */
-        {
-            Some(Direction::Bear)
-        }
-        std::cmp::Ordering::Equal =>
+        Some(Direction::Bear)
+    } else {
         /* Weird case where nobody was right */
-        {
-            None
-        }
+        None
     };
 
     Ok(FinishedRound {
@@ -765,11 +4037,425 @@ fn compute_round_close(
         open_price: round.open_price,
         bear_amount: round.bear_amount,
         bull_amount: round.bull_amount,
+        fee_amount: round.fee_amount,
+        tie_policy: config.tie_policy,
         winner,
         close_price,
+        winning_bucket,
+        bull_count: round.bull_count,
+        bear_count: round.bear_count,
+        house_seed: round.house_seed,
+        fee_model: round.fee_model.clone(),
+        winnings_fee_rate: round.winnings_fee_rate,
+    })
+}
+
+/**
+ * Index of the bucket a price move falls in
+ */
+fn compute_bucket(
+    bounds_bps: &[i32],
+    open_price: Uint128,
+    close_price: Uint128,
+) -> u8 {
+    /* Compared as prices, so no move gets rounded across a bound */
+    let close = Uint256::from(close_price) * Uint256::from(BPS_PRECISION);
+    bounds_bps
+        .iter()
+        .filter(|bound| {
+            let level = BPS_PRECISION as i128 + **bound as i128;
+            level <= 0
+                || close
+                    >= Uint256::from(open_price) * Uint256::from(level as u128)
+        })
+        .count() as u8
+}
+
+/**
+ * Buckets starting at or above the open price are on the bull side
+ */
+fn bucket_direction(bounds_bps: &[i32], bucket: u8) -> Direction {
+    match bucket.checked_sub(1) {
+        Some(lower) if bounds_bps[lower as usize] >= 0 => Direction::Bull,
+        _ => Direction::Bear,
+    }
+}
+
+fn assert_bucket_bounds(bounds_bps: &[i32]) -> StdResult<()> {
+    if bounds_bps.len() >= u8::MAX as usize
+        || bounds_bps.windows(2).any(|pair| pair[0] >= pair[1])
+    {
+        return Err(StdError::generic_err(
+            "Bucket bounds must be ascending and fewer than 255",
+        ));
+    }
+    Ok(())
+}
+
+fn load_bucket_pools(
+    storage: &dyn Storage,
+    round_id: Uint128,
+) -> StdResult<Vec<(u8, Uint128)>> {
+    BUCKET_POOLS
+        .prefix(round_id.u128())
+        .range(storage, None, None, Order::Ascending)
+        .collect()
+}
+
+/**
+ * Accrues the fees a finished pool keeps; a tied pool keeps what its tie
+ * policy leaves the house
+ */
+fn apply_tie_policy(
+    storage: &mut dyn Storage,
+    round: &FinishedRound,
+    token: &Option<AssetInfo>,
+) -> StdResult<()> {
+    /* Range rounds pool the market's stake by bucket */
+    if token.is_none() && round.winning_bucket.is_some() {
+        return Ok(());
+    }
+    let pool = load_finished_round_pool(storage, round, token)?;
+
+    /* One sided rounds are always refunded */
+    let tied = round.winner.is_none()
+        && !pool.bear_amount.is_zero()
+        && !pool.bull_amount.is_zero();
+    /* The house pool's seeds go back to it, see `settle_house_seed` */
+    let seeded = match token {
+        Some(_) => Uint128::zero(),
+        None => round.house_seed + round.house_seed,
+    };
+    let kept = match (tied, &round.tie_policy) {
+        (true, TiePolicy::RefundGross) => Uint128::zero(),
+        (true, TiePolicy::HouseTakes) => {
+            pool.total() - seeded + pool.fee_amount
+        }
+        _ => pool.fee_amount,
+    };
+
+    add_accumulated_fee(storage, token, kept)
+}
+
+fn record_round_stats(
+    storage: &mut dyn Storage,
+    round: &FinishedRound,
+) -> StdResult<()> {
+    let mut stats = ROUND_STATS.may_load(storage)?.unwrap_or_default();
+    match (&round.winning_bucket, &round.winner) {
+        (Some(_), _) => stats.range_rounds += 1,
+        (None, Some(Direction::Bull)) => stats.bull_wins += 1,
+        (None, Some(Direction::Bear)) => stats.bear_wins += 1,
+        (None, None) => stats.ties += 1,
+    }
+    ROUND_STATS.save(storage, &stats)
+}
+
+/**
+ * Takes the fee of a `FeeModel::OnWinnings` round off every pool with a
+ * winner as it finishes, recording it as the pool's fee
+ */
+fn apply_winnings_fees(
+    storage: &mut dyn Storage,
+    round: &mut FinishedRound,
+) -> StdResult<()> {
+    if round.fee_model != FeeModel::OnWinnings {
+        return Ok(());
+    }
+    let fee_rate = resolve_winnings_fee_rate(storage, round.winnings_fee_rate)?;
+
+    let won_total = match round.winning_bucket {
+        Some(winning_bucket) => {
+            let pools = load_bucket_pools(storage, round.id)?;
+            let backed = pools.iter().filter(|(_, amount)| !amount.is_zero());
+            if backed.clone().count() > 1
+                && backed.clone().any(|(bucket, _)| *bucket == winning_bucket)
+            {
+                backed
+                    .fold(Uint128::zero(), |total, (_, amount)| total + amount)
+            } else {
+                Uint128::zero()
+            }
+        }
+        None => compute_won_total(
+            round,
+            &load_finished_round_pool(storage, round, &None)?,
+            round.house_seed + round.house_seed,
+        ),
+    };
+    round.fee_amount = compute_winnings_fee(fee_rate, won_total)?;
+
+    for key in ROUND_POOLS
+        .prefix(round.id.u128())
+        .keys(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?
+    {
+        let fee_rate = match ROUND_POOL_FEE_RATES
+            .may_load(storage, (round.id.u128(), &key))?
+        {
+            Some(fee_rate) => fee_rate,
+            None => load_known_stake_token(storage, &key)?.gaming_fee,
+        };
+        let mut pool = ROUND_POOLS.load(storage, (round.id.u128(), &key))?;
+        pool.fee_amount = compute_winnings_fee(
+            fee_rate,
+            compute_won_total(round, &pool, Uint128::zero()),
+        )?;
+        ROUND_POOLS.save(storage, (round.id.u128(), &key), &pool)?;
+    }
+    Ok(())
+}
+
+/**
+ * What the players put in a pool if it has a winner, zero if it's refunded;
+ * the house pool's seeds pay no fee
+ */
+fn compute_won_total(
+    round: &FinishedRound,
+    pool: &RoundPool,
+    seeded: Uint128,
+) -> Uint128 {
+    if round.winner.is_none()
+        || pool.bear_amount.is_zero()
+        || pool.bull_amount.is_zero()
+    {
+        return Uint128::zero();
+    }
+    pool.total() - seeded
+}
+
+/**
+ * Gaming fee rate a `FeeModel::OnWinnings` round opening now will charge
+ */
+fn snapshot_winnings_fee_rate(config: &Config) -> Option<Uint128> {
+    match config.fee_model {
+        FeeModel::OnStake => None,
+        FeeModel::OnWinnings => Some(config.gaming_fee),
+    }
+}
+
+/**
+ * The rate recorded on a round, the current one for older rounds
+ */
+fn resolve_winnings_fee_rate(
+    storage: &dyn Storage,
+    fee_rate: Option<Uint128>,
+) -> StdResult<Uint128> {
+    match fee_rate {
+        Some(fee_rate) => Ok(fee_rate),
+        None => Ok(CONFIG.load(storage)?.gaming_fee),
+    }
+}
+
+fn compute_winnings_fee(
+    gaming_fee: Uint128,
+    total: Uint128,
+) -> StdResult<Uint128> {
+    gaming_fee
+        .checked_multiply_ratio(total, FEE_PRECISION * 100)
+        .map_err(|e| StdError::generic_err(e.to_string()))
+}
+
+/**
+ * Applies the tie policy to every pool of a round as it finishes; only then
+ * do its fees count as distributable
+ */
+fn apply_round_tie_policies(
+    storage: &mut dyn Storage,
+    round: &FinishedRound,
+) -> StdResult<()> {
+    apply_tie_policy(storage, round, &None)?;
+    apply_bucket_tie_policy(storage, round)?;
+    for key in ROUND_POOLS
+        .prefix(round.id.u128())
+        .keys(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?
+    {
+        let stake_token = load_known_stake_token(storage, &key)?;
+        apply_tie_policy(storage, round, &Some(stake_token.asset))?;
+    }
+    Ok(())
+}
+
+/**
+ * Like `apply_tie_policy`, for the buckets of range rounds
+ */
+fn apply_bucket_tie_policy(
+    storage: &mut dyn Storage,
+    round: &FinishedRound,
+) -> StdResult<()> {
+    let winning_bucket = match round.winning_bucket {
+        Some(winning_bucket) => winning_bucket,
+        None => return Ok(()),
+    };
+    let pools = load_bucket_pools(storage, round.id)?;
+
+    /* Rounds with a single backed bucket are always refunded */
+    let tied = pools.iter().filter(|(_, amount)| !amount.is_zero()).count() > 1
+        && !pools.iter().any(|(bucket, amount)| {
+            *bucket == winning_bucket && !amount.is_zero()
+        });
+    let kept = match (tied, &round.tie_policy) {
+        (true, TiePolicy::RefundGross) => Uint128::zero(),
+        (true, TiePolicy::HouseTakes) => {
+            pools
+                .iter()
+                .fold(Uint128::zero(), |total, (_, amount)| total + amount)
+                + round.fee_amount
+        }
+        _ => round.fee_amount,
+    };
+
+    add_accumulated_fee(storage, &None, kept)
+}
+
+/**
+ * Amount a bet is owed once its round has finished, whatever its market
+ */
+fn compute_settled_payout(
+    storage: &dyn Storage,
+    round: &FinishedRound,
+    game: &BetInfo,
+) -> StdResult<Uint128> {
+    match game.bucket {
+        Some(bucket) => {
+            let pools = load_bucket_pools(storage, round.id)?;
+            Ok(compute_bucket_payout(round, &pools, bucket, game))
+        }
+        None => {
+            let pool = load_finished_round_pool(storage, round, &game.token)?;
+            Ok(compute_bet_payout(round, &pool, game))
+        }
+    }
+}
+
+enum BetOutcome {
+    Win,
+    Loss,
+    Tie,
+}
+
+/**
+ * Mirrors `compute_settled_payout`; one-sided pools count as ties
+ */
+fn compute_settled_outcome(
+    storage: &dyn Storage,
+    round: &FinishedRound,
+    game: &BetInfo,
+) -> StdResult<BetOutcome> {
+    let won = match game.bucket {
+        Some(bucket) => {
+            let pools = load_bucket_pools(storage, round.id)?;
+            let winning_pool = pools
+                .iter()
+                .find(|(pool_bucket, _)| {
+                    Some(*pool_bucket) == round.winning_bucket
+                })
+                .map(|(_, amount)| *amount)
+                .unwrap_or_default();
+            if pools.iter().filter(|(_, amount)| !amount.is_zero()).count() <= 1
+                || winning_pool.is_zero()
+            {
+                return Ok(BetOutcome::Tie);
+            }
+            Some(bucket) == round.winning_bucket
+        }
+        None => {
+            let pool = load_finished_round_pool(storage, round, &game.token)?;
+            if pool.bear_amount.is_zero() || pool.bull_amount.is_zero() {
+                return Ok(BetOutcome::Tie);
+            }
+            match &round.winner {
+                Some(winner) => *winner == game.direction,
+                None => return Ok(BetOutcome::Tie),
+            }
+        }
+    };
+
+    Ok(if won {
+        BetOutcome::Win
+    } else {
+        BetOutcome::Loss
     })
 }
 
+/**
+ * Parimutuel payout across all buckets of a range round
+ */
+fn compute_bucket_payout(
+    round: &FinishedRound,
+    pools: &[(u8, Uint128)],
+    bucket: u8,
+    game: &BetInfo,
+) -> Uint128 {
+    if pools.iter().filter(|(_, amount)| !amount.is_zero()).count() <= 1 {
+        return game.amount;
+    }
+
+    let total = pools
+        .iter()
+        .fold(Uint128::zero(), |total, (_, amount)| total + amount);
+    let winning_pool = pools
+        .iter()
+        .find(|(pool_bucket, _)| Some(*pool_bucket) == round.winning_bucket)
+        .map(|(_, amount)| *amount)
+        .unwrap_or_default();
+
+    if winning_pool.is_zero() {
+        compute_tie_payout(round, game)
+    } else if Some(bucket) == round.winning_bucket {
+        compute_prize_pool(round, total, round.fee_amount)
+            .multiply_ratio(game.amount, winning_pool)
+    } else {
+        Uint128::zero()
+    }
+}
+
+/**
+ * What the winners of a pool share; `FeeModel::OnWinnings` rounds took their
+ * fee off it
+ */
+fn compute_prize_pool(
+    round: &FinishedRound,
+    total: Uint128,
+    fee_amount: Uint128,
+) -> Uint128 {
+    match round.fee_model {
+        FeeModel::OnStake => total,
+        FeeModel::OnWinnings => total - fee_amount,
+    }
+}
+
+fn compute_tie_payout(round: &FinishedRound, game: &BetInfo) -> Uint128 {
+    match round.tie_policy {
+        TiePolicy::RefundNet => game.amount,
+        TiePolicy::RefundGross => game.amount + game.fee,
+        TiePolicy::HouseTakes => Uint128::zero(),
+    }
+}
+
+/**
+ * Amount a bet is owed once its round has finished
+ */
+fn compute_bet_payout(
+    round: &FinishedRound,
+    pool: &RoundPool,
+    game: &BetInfo,
+) -> Uint128 {
+    if pool.bear_amount.is_zero() || pool.bull_amount.is_zero() {
+        return game.amount;
+    }
+
+    match &round.winner {
+        Some(winner) if *winner == game.direction => {
+            compute_prize_pool(round, pool.total(), pool.fee_amount)
+                .multiply_ratio(game.amount, pool.side_amount(winner))
+        }
+        Some(_) => Uint128::zero(),
+        None => compute_tie_payout(round, game),
+    }
+}
+
 fn assert_not_haulted(deps: Deps) -> StdResult<bool> {
     let is_haulted = IS_HAULTED.load(deps.storage)?;
     if is_haulted {
@@ -808,6 +4494,83 @@ fn assert_is_admin(deps: Deps, info: MessageInfo, env: Env) -> StdResult<bool> {
     Ok(true)
 }
 
+pub fn get_asset_transfer_msg(
+    asset: &AssetInfo,
+    recipient: &Addr,
+    amount: Uint128,
+) -> StdResult<CosmosMsg> {
+    match asset {
+        AssetInfo::NativeToken { denom } => {
+            get_bank_transfer_to_msg(recipient, denom, amount)
+        }
+        AssetInfo::Token { contract_addr } => {
+            get_cw20_transfer_msg(contract_addr, recipient, amount)
+        }
+    }
+}
+
+pub fn get_staking_hook_msg(
+    asset: &AssetInfo,
+    staking_addr: &Addr,
+    amount: Uint128,
+) -> StdResult<CosmosMsg> {
+    let hook_msg = to_binary(&StakingHookMsg::DistributeRewards {})?;
+
+    match asset {
+        AssetInfo::NativeToken { denom } => Ok(WasmMsg::Execute {
+            contract_addr: staking_addr.to_string(),
+            msg: hook_msg,
+            funds: vec![Coin {
+                denom: denom.clone(),
+                amount,
+            }],
+        }
+        .into()),
+        AssetInfo::Token { contract_addr } => Ok(WasmMsg::Execute {
+            contract_addr: contract_addr.to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Send {
+                contract: staking_addr.to_string(),
+                amount,
+                msg: hook_msg,
+            })?,
+            funds: vec![],
+        }
+        .into()),
+    }
+}
+
+pub fn get_position_mint_msg(
+    nft_addr: &Addr,
+    player: &Addr,
+    metadata: PositionMetadata,
+) -> StdResult<CosmosMsg> {
+    let mint_msg = PositionNftExecuteMsg::Mint {
+        token_id: position_token_id(metadata.round_id, player),
+        owner: player.to_string(),
+        token_uri: None,
+        extension: metadata,
+    };
+
+    Ok(WasmMsg::Execute {
+        contract_addr: nft_addr.to_string(),
+        msg: to_binary(&mint_msg)?,
+        funds: vec![],
+    }
+    .into())
+}
+
+pub fn get_position_burn_msg(
+    nft_addr: &Addr,
+    token_id: String,
+) -> StdResult<CosmosMsg> {
+    Ok(WasmMsg::Execute {
+        contract_addr: nft_addr.to_string(),
+        msg: to_binary(&PositionNftExecuteMsg::Burn { token_id })?,
+        funds: vec![],
+    }
+    .into())
+}
+
 pub fn get_cw20_transfer_msg(
     token_addr: &Addr,
     recipient: &Addr,
@@ -869,6 +4632,21 @@ pub fn get_cw20_burn_from_msg(
     Ok(cw20_transfer_msg)
 }
 
+pub fn get_cw20_burn_msg(
+    token_addr: &Addr,
+    amount: Uint128,
+) -> StdResult<CosmosMsg> {
+    let burn_cw20_msg = Cw20ExecuteMsg::Burn { amount };
+    let exec_cw20_burn_msg = WasmMsg::Execute {
+        contract_addr: token_addr.into(),
+        msg: to_binary(&burn_cw20_msg)?,
+        funds: vec![],
+    };
+
+    let cw20_burn_msg: CosmosMsg = exec_cw20_burn_msg.into();
+    Ok(cw20_burn_msg)
+}
+
 pub fn get_bank_transfer_to_msg(
     recipient: &Addr,
     denom: &str,
@@ -885,3 +4663,15 @@ pub fn get_bank_transfer_to_msg(
     let transfer_bank_cosmos_msg: CosmosMsg = transfer_bank_msg.into();
     Ok(transfer_bank_cosmos_msg)
 }
+
+pub fn get_bank_burn_msg(denom: &str, amount: Uint128) -> StdResult<CosmosMsg> {
+    let burn_bank_msg = cosmwasm_std::BankMsg::Burn {
+        amount: vec![Coin {
+            denom: denom.to_string(),
+            amount,
+        }],
+    };
+
+    let burn_bank_cosmos_msg: CosmosMsg = burn_bank_msg.into();
+    Ok(burn_bank_cosmos_msg)
+}
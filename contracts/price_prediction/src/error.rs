@@ -1,6 +1,6 @@
 use thiserror::Error;
 
-use cosmwasm_std::StdError;
+use cosmwasm_std::{StdError, Uint128};
 
 #[derive(Error, Debug, PartialEq)]
 pub enum ContractError {
@@ -12,4 +12,34 @@ pub enum ContractError {
 
     #[error("The sum of wallet ratio is not equal to 1")]
     WrongRatio {},
+
+    #[error("Rounds can't last zero seconds")]
+    ZeroRoundDuration {},
+
+    #[error("The burn and gaming fees can't exceed 100%")]
+    FeesTooHigh {},
+
+    #[error("{name} can't exceed 100%")]
+    ShareTooHigh { name: String },
+
+    #[error("The minimum bet can't exceed the maximums")]
+    InvalidBetLimits {},
+
+    #[error("Minimum bet is {minimum}")]
+    BetTooSmall { minimum: Uint128 },
+
+    #[error("Maximum bet is {maximum}")]
+    BetTooLarge { maximum: Uint128 },
+
+    #[error("Positions in a round can't exceed {maximum}")]
+    PositionTooLarge { maximum: Uint128 },
+
+    #[error("Rounds can't take more than {maximum}")]
+    RoundPoolFull { maximum: Uint128 },
+
+    #[error("Positions left in a round can't be under {minimum}")]
+    PositionTooSmall { minimum: Uint128 },
+
+    #[error("Invalid denom {denom}")]
+    InvalidDenom { denom: String },
 }
@@ -1,5 +1,9 @@
-use crate::{Config, FinishedRound, LiveRound, NextRound};
-use cosmwasm_std::{Addr, Uint128};
+use crate::{
+    AssetAmount, AssetInfo, Config, FeeDistribution, FeeTier, FinishedRound,
+    GlobalStats, HousePool, LiveRound, NextRound, PlayerStats, RoundPool,
+    RoundStats, StakeToken, StakingState, StrikeMarket, WalletInfo,
+};
+use cosmwasm_std::{Addr, Decimal, Timestamp, Uint128};
 use cw_storage_plus::{Index, IndexList, IndexedMap, Item, Map, MultiIndex};
 use hopers_bet::price_prediction::Direction;
 use schemars::JsonSchema;
@@ -13,9 +17,115 @@ pub const NEXT_ROUND: Item<NextRound> = Item::new("next_round");
 /* The live round; not accepting bets */
 pub const LIVE_ROUND: Item<LiveRound> = Item::new("live_round");
 
+/* Fees not distributed yet */
 pub const ACCUMULATED_FEE: Item<u128> = Item::new("accumulated_fee");
+pub const FEE_RECIPIENTS: Item<Vec<WalletInfo>> = Item::new("fee_recipients");
+pub const STAKING_STATE: Item<StakingState> = Item::new("staking_state");
+pub const STAKERS: Map<&Addr, StakerInfo> = Map::new("stakers");
+pub const HOUSE_POOL: Item<HousePool> = Item::new("house_pool");
+pub const HOUSE_SHARES: Map<&Addr, Uint128> = Map::new("house_shares");
+pub const HOUSE_REQUESTS: Map<&Addr, HouseRequest> = Map::new("house_requests");
+/* NAV and shares each house epoch settled at */
+pub const HOUSE_EPOCHS: Map<u64, HouseEpoch> = Map::new("house_epochs");
+pub const FEE_DISTRIBUTIONS: Map<u64, FeeDistribution> =
+    Map::new("fee_distributions");
+pub const NEXT_FEE_DISTRIBUTION_ID: Item<u64> =
+    Item::new("next_fee_distribution_id");
+
+/* Fees of the whitelisted tokens, keyed by `AssetInfo::key` */
+pub const TOKEN_ACCUMULATED_FEE: Map<&str, u128> =
+    Map::new("token_accumulated_fee");
 
 pub const ROUNDS: Map<u128, FinishedRound> = Map::new("rounds");
+pub const ROUND_STATS: Item<RoundStats> = Item::new("round_stats");
+pub const GLOBAL_STATS: Item<GlobalStats> = Item::new("global_stats");
+/* Everyone who ever bet the market's stake */
+pub const PLAYERS: Map<&Addr, bool> = Map::new("players");
+
+pub const STRIKE_MARKETS: Map<u128, StrikeMarket> = Map::new("strike_markets");
+pub const NEXT_STRIKE_MARKET_ID: Item<u128> =
+    Item::new("next_strike_market_id");
+/* Addresses besides the admin allowed to open strike markets */
+pub const STRIKE_CREATORS: Map<&Addr, bool> = Map::new("strike_creators");
+
+/* Bucket bounds of range rounds, fixed when the round opens for bets */
+pub const ROUND_BUCKETS: Map<u128, Vec<i32>> = Map::new("round_buckets");
+/* Net amount bet on a bucket of a range round */
+pub const BUCKET_POOLS: Map<(u128, u8), Uint128> = Map::new("bucket_pools");
+
+/* Winnings of resolved bets not paid out yet, by (player, `AssetInfo::key`) */
+pub const CLAIMABLE: Map<(&Addr, &str), AssetAmount> = Map::new("claimable");
+
+/* Referrer of each referred player */
+pub const REFERRERS: Map<&Addr, Addr> = Map::new("referrers");
+/* (referrer, player) pairs, to list a referrer's players */
+pub const REFERRALS: Map<(&Addr, &Addr), bool> = Map::new("referrals");
+/* Lifetime referral rewards, by (referrer, `AssetInfo::key`) */
+pub const REFERRAL_EARNINGS: Map<(&Addr, &str), AssetAmount> =
+    Map::new("referral_earnings");
+/* Referral rewards not claimed yet, by (referrer, `AssetInfo::key`) */
+pub const REFERRAL_CLAIMABLE: Map<(&Addr, &str), AssetAmount> =
+    Map::new("referral_claimable");
+
+/* Bets removed from `bet_info_storage` as they're paid out, by
+ * (round, player), so rounds keep listing their bettors */
+pub const SETTLED_BETS: Map<(u128, &Addr), BetInfo> = Map::new("settled_bets");
+
+/*
+ * Payouts of tokenized bets settled by `SettleRound`, kept for whoever sends
+ * the position in, by (round, player)
+ */
+pub const POSITION_PAYOUTS: Map<(u128, &Addr), AssetAmount> =
+    Map::new("position_payouts");
+
+/* (player, operator) pairs; operators may trigger the player's claims */
+pub const CLAIM_OPERATORS: Map<(&Addr, &Addr), bool> =
+    Map::new("claim_operators");
+
+pub const STAKE_TOKENS: Map<&str, StakeToken> = Map::new("stake_tokens");
+/* Last settings of every token ever whitelisted; kept on removal so the
+ * pools and fees of removed tokens can still be settled */
+pub const KNOWN_STAKE_TOKENS: Map<&str, StakeToken> =
+    Map::new("known_stake_tokens");
+
+pub const FEE_TIERS: Item<Vec<FeeTier>> = Item::new("fee_tiers");
+/* Net stake a player bet in the market's stake, by (player, volume epoch) */
+pub const PLAYER_VOLUME: Map<(&Addr, u64), Uint128> = Map::new("player_volume");
+/* Whitelisted token pools; the round itself holds the market's stake */
+pub const ROUND_POOLS: Map<(u128, &str), RoundPool> = Map::new("round_pools");
+/* Gaming fee rate of the token pools of `FeeModel::OnWinnings` rounds, fixed
+ * by the pool's first bet */
+pub const ROUND_POOL_FEE_RATES: Map<(u128, &str), Uint128> =
+    Map::new("round_pool_fee_rates");
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, JsonSchema)]
+pub struct StakerInfo {
+    pub amount: Uint128,
+    /* `reward_per_share` when `pending_reward` was last brought up to date */
+    pub reward_index: Decimal,
+    pub pending_reward: Uint128,
+    /* Every stake restarts the lock-up of the whole amount */
+    #[serde(default)]
+    pub locked_until: Timestamp,
+}
+
+/**
+ * A depositor's share changes queued for a house epoch
+ */
+#[derive(Serialize, Deserialize, Clone, Debug, Default, JsonSchema)]
+pub struct HouseRequest {
+    pub epoch: u64,
+    pub deposit: Uint128,
+    pub shares: Uint128,
+    /* Paid for shares withdrawn at a past epoch */
+    pub claimable: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
+pub struct HouseEpoch {
+    pub nav: Uint128,
+    pub total_shares: Uint128,
+}
 
 #[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
 pub struct BetInfo {
@@ -23,6 +133,18 @@ pub struct BetInfo {
     pub round_id: Uint128,
     pub amount: Uint128,
     pub direction: Direction,
+    /* Gaming fee paid on top of `amount` */
+    #[serde(default)]
+    pub fee: Uint128,
+    /* Whitelisted token of the bet; `None` is the market's stake */
+    #[serde(default)]
+    pub token: Option<AssetInfo>,
+    /* Set if the bet is tokenized; the holder of its token is paid */
+    #[serde(default)]
+    pub position_nft: Option<Addr>,
+    /* Bucket of a range round bet; `direction` is the side it lies on */
+    #[serde(default)]
+    pub bucket: Option<u8>,
 }
 
 /// Primary key for betinfo: (round_id, player)
@@ -34,6 +156,8 @@ pub fn bet_info_key(round_id: u128, player: &Addr) -> BetInfoKey {
 
 /// Defines incides for accessing bids
 pub struct BetInfoIndicies<'a> {
+    /* Tokenized bets are indexed under their position contract, they
+     * belong to whoever holds the token */
     pub player: MultiIndex<'a, Addr, BetInfo, BetInfoKey>,
 }
 
@@ -50,7 +174,9 @@ pub fn bet_info_storage<'a>(
 ) -> IndexedMap<'a, BetInfoKey, BetInfo, BetInfoIndicies<'a>> {
     let indexes = BetInfoIndicies {
         player: MultiIndex::new(
-            |_pk: &[u8], d: &BetInfo| d.player.clone(),
+            |_pk: &[u8], d: &BetInfo| {
+                d.position_nft.clone().unwrap_or_else(|| d.player.clone())
+            },
             "bet_info",
             "bet_info_collection",
         ),
@@ -58,14 +184,54 @@ pub fn bet_info_storage<'a>(
     IndexedMap::new("bet_info", indexes)
 }
 
+/// Indices of the leaderboard
+pub struct PlayerStatsIndicies<'a> {
+    pub pnl: MultiIndex<'a, i128, PlayerStats, Addr>,
+    pub volume: MultiIndex<'a, u128, PlayerStats, Addr>,
+}
+
+impl<'a> IndexList<PlayerStats> for PlayerStatsIndicies<'a> {
+    fn get_indexes(
+        &'_ self,
+    ) -> Box<dyn Iterator<Item = &'_ dyn Index<PlayerStats>> + '_> {
+        let v: Vec<&dyn Index<PlayerStats>> = vec![&self.pnl, &self.volume];
+        Box::new(v.into_iter())
+    }
+}
+
+pub fn player_stats_storage<'a>(
+) -> IndexedMap<'a, Addr, PlayerStats, PlayerStatsIndicies<'a>> {
+    let indexes = PlayerStatsIndicies {
+        pnl: MultiIndex::new(
+            |_pk: &[u8], d: &PlayerStats| d.net_pnl,
+            "player_stats",
+            "player_stats__pnl",
+        ),
+        volume: MultiIndex::new(
+            |_pk: &[u8], d: &PlayerStats| d.total_staked.u128(),
+            "player_stats",
+            "player_stats__volume",
+        ),
+    };
+    IndexedMap::new("player_stats", indexes)
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub struct MyGameResponse {
     pub my_game_list: Vec<BetInfo>,
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct RoundBetsResponse {
+    pub bets: Vec<BetInfo>,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub struct PendingRewardResponse {
     pub pending_reward: Uint128,
+    /* Rewards in whitelisted tokens */
+    pub token_rewards: Vec<AssetAmount>,
 }
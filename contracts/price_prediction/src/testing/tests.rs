@@ -1,7 +1,8 @@
 use cosmwasm_std::testing::mock_env;
 use cosmwasm_std::{
     coins, to_binary, Addr, Binary, BlockInfo, Coin, CosmosMsg, Decimal, Empty,
-    Response, StdResult, Timestamp, Uint128, WasmMsg,
+    MessageInfo, Response, StdError, StdResult, Storage, Timestamp, Uint128,
+    WasmMsg,
 };
 use cw20::{BalanceResponse, Cw20Coin, Cw20ExecuteMsg, Cw20QueryMsg};
 use cw_multi_test::{App, Contract, ContractWrapper, Executor};
@@ -10,25 +11,71 @@ use hopers_bet::fast_oracle::{
     msg::InstantiateMsg as FastOracleInstantiateMsg,
     msg::QueryMsg as FastOracleQueryMsg,
 };
+use hopers_bet::position_nft::{
+    msg::Cw721ReceiveMsg, msg::OwnerOfResponse,
+    msg::QueryMsg as PositionNftQueryMsg, msg::TokensResponse,
+    PositionMetadata,
+};
+use hopers_bet::price_prediction::{
+    msg::{ExecuteMsg, InstantiateMsg, QueryMsg, ReceiveMsg},
+    response::{
+        ConfigResponse, FeeDistributionsResponse, FinishedRoundsResponse,
+        HousePoolResponse, HouseSharesResponse, LeaderboardResponse,
+        OddsResponse, PlayerFeeTierResponse, ReferralEarningsResponse,
+        ReferredPlayersResponse, RoundAtTimeResponse, RoundBucketsResponse,
+        RoundPoolsResponse, SimulateBetResponse, StakerResponse,
+        StatusResponse,
+    },
+    Config, PartialConfig,
+};
 use hopers_bet::price_prediction::{
-    msg::{ExecuteMsg, InstantiateMsg, QueryMsg},
-    response::{ConfigResponse, StatusResponse},
-    Config,
+    AssetAmount, AssetInfo, Direction, FeeModel, FeeStaking, FeeTier,
+    FeeTierRequirement, FinishedRound, GlobalStats, HousePool,
+    LeaderboardOrder, OrderBy, PlayerStats, RoundPool, RoundStats, StakeToken,
+    StakingState, TiePolicy, WalletInfo, STRIKE_MARKET_ID_OFFSET,
 };
-use hopers_bet::price_prediction::{Direction, FinishedRound, WalletInfo};
 
 use cw20_base::msg::InstantiateMsg as Cw20InstantiateMsg;
+use serde::{Deserialize, Serialize};
 
 // use std::borrow::BorrowMut;
 use std::convert::TryInto;
 // use std::ops::Add;
 
-use crate::state::{MyGameResponse, PendingRewardResponse};
+use crate::state::{MyGameResponse, PendingRewardResponse, RoundBetsResponse};
 
 fn mock_app() -> App {
     App::default()
 }
 
+fn default_config() -> Config {
+    Config {
+        next_round_seconds: Uint128::new(600u128),
+        fast_oracle_addr: Addr::unchecked("fast_oracle"),
+        minimum_bet: Uint128::new(1u128),
+        burn_fee: Uint128::new(100u128),
+        gaming_fee: Uint128::new(200u128),
+        token_addr: Addr::unchecked("token_contract"),
+        tie_policy: TiePolicy::RefundNet,
+        tie_band_bps: Uint128::zero(),
+        withdrawal_penalty: Uint128::zero(),
+        stake_denom: None,
+        push_settlement: false,
+        position_nft_addr: None,
+        bucket_bounds_bps: vec![],
+        referral_share: Uint128::zero(),
+        fee_staking: FeeStaking::Disabled,
+        staking_share: Uint128::zero(),
+        staking_lock_seconds: Uint128::zero(),
+        house_seed_cap: Uint128::zero(),
+        house_fee_share: Uint128::zero(),
+        fee_model: FeeModel::OnStake,
+        maximum_bet: Uint128::zero(),
+        maximum_position: Uint128::zero(),
+        maximum_round_pool: Uint128::zero(),
+    }
+}
+
 pub fn contract_price_prediction() -> Box<dyn Contract<Empty>> {
     let contract = ContractWrapper::new(
         crate::contract::execute,
@@ -79,6 +126,110 @@ pub fn contract_cw20() -> Box<dyn Contract<Empty>> {
     Box::new(contract)
 }
 
+/* The cw721 messages the position mock handles */
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "snake_case")]
+enum MockNftExecuteMsg {
+    Mint {
+        token_id: String,
+        owner: String,
+        token_uri: Option<String>,
+        extension: PositionMetadata,
+    },
+    Burn {
+        token_id: String,
+    },
+    TransferNft {
+        recipient: String,
+        token_id: String,
+    },
+    SendNft {
+        contract: String,
+        token_id: String,
+        msg: Binary,
+    },
+}
+
+fn assert_mock_nft_owner(
+    storage: &dyn Storage,
+    token_id: &str,
+    info: &MessageInfo,
+) -> StdResult<()> {
+    let owner = storage
+        .get(token_id.as_bytes())
+        .ok_or_else(|| StdError::not_found("token"))?;
+    if owner != info.sender.as_bytes() {
+        return Err(StdError::generic_err("Unauthorized"));
+    }
+    Ok(())
+}
+
+pub fn contract_position_nft() -> Box<dyn Contract<Empty>> {
+    let contract = ContractWrapper::new(
+        |deps, _, info, msg: MockNftExecuteMsg| -> StdResult<Response> {
+            match msg {
+                MockNftExecuteMsg::Mint {
+                    token_id, owner, ..
+                } => {
+                    if deps.storage.get(token_id.as_bytes()).is_some() {
+                        return Err(StdError::generic_err("token_id taken"));
+                    }
+                    deps.storage.set(token_id.as_bytes(), owner.as_bytes());
+                }
+                MockNftExecuteMsg::Burn { token_id } => {
+                    assert_mock_nft_owner(deps.storage, &token_id, &info)?;
+                    deps.storage.remove(token_id.as_bytes());
+                }
+                MockNftExecuteMsg::TransferNft {
+                    recipient,
+                    token_id,
+                } => {
+                    assert_mock_nft_owner(deps.storage, &token_id, &info)?;
+                    deps.storage.set(token_id.as_bytes(), recipient.as_bytes());
+                }
+                MockNftExecuteMsg::SendNft {
+                    contract,
+                    token_id,
+                    msg,
+                } => {
+                    assert_mock_nft_owner(deps.storage, &token_id, &info)?;
+                    deps.storage.set(token_id.as_bytes(), contract.as_bytes());
+                    return Ok(Response::new().add_message(WasmMsg::Execute {
+                        contract_addr: contract,
+                        msg: to_binary(&ExecuteMsg::ReceiveNft(
+                            Cw721ReceiveMsg {
+                                sender: info.sender.to_string(),
+                                token_id,
+                                msg,
+                            },
+                        ))?,
+                        funds: vec![],
+                    }));
+                }
+            }
+            Ok(Response::default())
+        },
+        |_, _, _, _: Empty| -> StdResult<Response> { Ok(Response::default()) },
+        |deps, _, msg: PositionNftQueryMsg| -> StdResult<Binary> {
+            match msg {
+                PositionNftQueryMsg::OwnerOf { token_id, .. } => {
+                    let owner = deps
+                        .storage
+                        .get(token_id.as_bytes())
+                        .ok_or_else(|| StdError::not_found("token"))?;
+                    to_binary(&OwnerOfResponse {
+                        owner: String::from_utf8(owner).unwrap(),
+                    })
+                }
+                PositionNftQueryMsg::Tokens { .. } => {
+                    to_binary(&TokensResponse { tokens: vec![] })
+                }
+            }
+        },
+    );
+    Box::new(contract)
+}
+
 fn update_price(
     router: &mut App,
     config: ConfigResponse,
@@ -117,6 +268,27 @@ fn start_next_round(
         .unwrap();
 }
 
+/* What keepers do as rounds finish */
+fn settle_round(
+    router: &mut App,
+    prediction_market_addr: &Addr,
+    round_id: Uint128,
+) {
+    let settle_msg: CosmosMsg = CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: prediction_market_addr.to_string(),
+        msg: to_binary(&ExecuteMsg::SettleRound {
+            round_id,
+            limit: None,
+        })
+        .unwrap(),
+        funds: vec![],
+    });
+
+    router
+        .execute_multi(Addr::unchecked("keeper"), [settle_msg].to_vec())
+        .unwrap();
+}
+
 fn init_fast_oracle_contract(router: &mut App, owner: &Addr) -> Addr {
     // println!("prediction_market_code_id, {:?}", prediction_market_code_id);
 
@@ -239,16 +411,26 @@ fn execute_bet(
         Direction::Bear => {
             bet_msg = CosmosMsg::Wasm(WasmMsg::Execute {
                 contract_addr: prediction_market_addr.to_string(),
-                msg: to_binary(&ExecuteMsg::BetBear { amount, round_id })
-                    .unwrap(),
+                msg: to_binary(&ExecuteMsg::BetBear {
+                    amount,
+                    round_id,
+                    token: None,
+                    referrer: None,
+                })
+                .unwrap(),
                 funds: vec![],
             });
         }
         Direction::Bull => {
             bet_msg = CosmosMsg::Wasm(WasmMsg::Execute {
                 contract_addr: prediction_market_addr.to_string(),
-                msg: to_binary(&ExecuteMsg::BetBull { amount, round_id })
-                    .unwrap(),
+                msg: to_binary(&ExecuteMsg::BetBull {
+                    amount,
+                    round_id,
+                    token: None,
+                    referrer: None,
+                })
+                .unwrap(),
                 funds: vec![],
             });
         }
@@ -265,14 +447,7 @@ fn test_bet() {
     let mut router = mock_app();
     let owner = Addr::unchecked("owner");
 
-    let default_config: Config = Config {
-        next_round_seconds: Uint128::new(600u128),
-        fast_oracle_addr: Addr::unchecked("fast_oracle"),
-        minimum_bet: Uint128::new(1u128),
-        burn_fee: Uint128::new(100u128),
-        gaming_fee: Uint128::new(200u128),
-        token_addr: Addr::unchecked("token_contract"),
-    };
+    let default_config = default_config();
 
     let prediction_market_addr =
         create_prediction_market(&mut router, &owner, default_config.clone());
@@ -390,7 +565,13 @@ fn test_bet() {
 
     let claim_msg: CosmosMsg = CosmosMsg::Wasm(WasmMsg::Execute {
         contract_addr: prediction_market_addr.to_string(),
-        msg: to_binary(&ExecuteMsg::CollectWinnings {}).unwrap(),
+        msg: to_binary(&ExecuteMsg::CollectWinnings {
+            round_ids: None,
+            limit: None,
+            player: None,
+            recipient: None,
+        })
+        .unwrap(),
         funds: vec![],
     });
 
@@ -400,7 +581,13 @@ fn test_bet() {
 
     let claim_msg: CosmosMsg = CosmosMsg::Wasm(WasmMsg::Execute {
         contract_addr: prediction_market_addr.to_string(),
-        msg: to_binary(&ExecuteMsg::CollectWinnings {}).unwrap(),
+        msg: to_binary(&ExecuteMsg::CollectWinnings {
+            round_ids: None,
+            limit: None,
+            player: None,
+            recipient: None,
+        })
+        .unwrap(),
         funds: vec![],
     });
 
@@ -446,10 +633,10 @@ fn test_bet() {
 
     //------------------------------------------------Test Distribute Reward--------------------------------------------------------------------//
 
-    let distribute_msg: CosmosMsg = CosmosMsg::Wasm(WasmMsg::Execute {
+    let recipients_msg: CosmosMsg = CosmosMsg::Wasm(WasmMsg::Execute {
         contract_addr: prediction_market_addr.to_string(),
-        msg: to_binary(&ExecuteMsg::DistributeFund {
-            dev_wallet_list: vec![
+        msg: to_binary(&ExecuteMsg::SetFeeRecipients {
+            recipients: vec![
                 WalletInfo {
                     address: Addr::unchecked("admin1"),
                     ratio: Decimal::from_ratio(50 as u128, 100 as u128),
@@ -463,9 +650,17 @@ fn test_bet() {
         .unwrap(),
         funds: vec![],
     });
+    let distribute_msg: CosmosMsg = CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: prediction_market_addr.to_string(),
+        msg: to_binary(&ExecuteMsg::DistributeFund {}).unwrap(),
+        funds: vec![],
+    });
 
     router
-        .execute_multi(Addr::unchecked("owner"), [distribute_msg].to_vec())
+        .execute_multi(
+            Addr::unchecked("owner"),
+            [recipients_msg, distribute_msg].to_vec(),
+        )
         .unwrap();
 
     let admin1_balance: BalanceResponse = router
@@ -502,3 +697,3688 @@ fn test_bet() {
     println!("admin2 balance {:?}", admin2_balance);
     println!("contract balance {:?}", contract_balance);
 }
+
+#[test]
+fn test_tie_band() {
+    let mut router = mock_app();
+    let owner = Addr::unchecked("owner");
+
+    let default_config = Config {
+        tie_band_bps: Uint128::new(100u128),
+        ..default_config()
+    };
+
+    let prediction_market_addr =
+        create_prediction_market(&mut router, &owner, default_config);
+
+    start_next_round(&mut router, &prediction_market_addr, &owner);
+
+    let config: ConfigResponse = router
+        .wrap()
+        .query_wasm_smart(
+            prediction_market_addr.to_string(),
+            &QueryMsg::Config {},
+        )
+        .unwrap();
+
+    execute_bet(
+        &mut router,
+        Addr::unchecked("user1"),
+        Uint128::new(100),
+        Direction::Bear,
+        &config.token_addr,
+        &prediction_market_addr,
+        Uint128::zero(),
+    );
+
+    execute_bet(
+        &mut router,
+        Addr::unchecked("user2"),
+        Uint128::new(50),
+        Direction::Bull,
+        &config.token_addr,
+        &prediction_market_addr,
+        Uint128::zero(),
+    );
+
+    /* A 0.5% move stays inside the 1% band */
+    start_next_round(&mut router, &prediction_market_addr, &owner);
+    update_price(&mut router, config, Uint128::new(1_005_000), &owner);
+    start_next_round(&mut router, &prediction_market_addr, &owner);
+
+    let round: FinishedRound = router
+        .wrap()
+        .query_wasm_smart(
+            prediction_market_addr.clone(),
+            &QueryMsg::FinishedRound {
+                round_id: Uint128::zero(),
+            },
+        )
+        .unwrap();
+    assert!(round.winner.is_none());
+
+    settle_round(&mut router, &prediction_market_addr, Uint128::zero());
+    let pending_reward_user1: PendingRewardResponse = router
+        .wrap()
+        .query_wasm_smart(
+            prediction_market_addr.clone(),
+            &QueryMsg::MyPendingReward {
+                player: Addr::unchecked("user1"),
+            },
+        )
+        .unwrap();
+    let pending_reward_user2: PendingRewardResponse = router
+        .wrap()
+        .query_wasm_smart(
+            prediction_market_addr,
+            &QueryMsg::MyPendingReward {
+                player: Addr::unchecked("user2"),
+            },
+        )
+        .unwrap();
+
+    /* Net of the 1% burn and 2% gaming fee */
+    assert_eq!(pending_reward_user1.pending_reward, Uint128::new(97));
+    assert_eq!(pending_reward_user2.pending_reward, Uint128::new(49));
+}
+
+/**
+ * Plays a round user1 bets 100 Bear and user2 50 Bull on that closes at the
+ * open price, then has both players claim
+ */
+fn play_tied_round(router: &mut App, tie_policy: TiePolicy) -> (Addr, Addr) {
+    let owner = Addr::unchecked("owner");
+
+    let prediction_market_addr = create_prediction_market(
+        router,
+        &owner,
+        Config {
+            tie_policy,
+            ..default_config()
+        },
+    );
+
+    start_next_round(router, &prediction_market_addr, &owner);
+
+    let config: ConfigResponse = router
+        .wrap()
+        .query_wasm_smart(
+            prediction_market_addr.to_string(),
+            &QueryMsg::Config {},
+        )
+        .unwrap();
+
+    for (user, amount, direction) in [
+        ("user1", 100u128, Direction::Bear),
+        ("user2", 50u128, Direction::Bull),
+    ] {
+        execute_bet(
+            router,
+            Addr::unchecked(user),
+            Uint128::new(amount),
+            direction,
+            &config.token_addr,
+            &prediction_market_addr,
+            Uint128::zero(),
+        );
+    }
+
+    start_next_round(router, &prediction_market_addr, &owner);
+    start_next_round(router, &prediction_market_addr, &owner);
+
+    for user in ["user1", "user2"] {
+        let claim_msg: CosmosMsg = CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: prediction_market_addr.to_string(),
+            msg: to_binary(&ExecuteMsg::CollectWinnings {
+                round_ids: None,
+                limit: None,
+                player: None,
+                recipient: None,
+            })
+            .unwrap(),
+            funds: vec![],
+        });
+        router
+            .execute_multi(Addr::unchecked(user), [claim_msg].to_vec())
+            .unwrap();
+    }
+
+    (prediction_market_addr, config.token_addr)
+}
+
+fn query_cw20_balance(router: &App, token_addr: &Addr, address: &str) -> u128 {
+    let balance: BalanceResponse = router
+        .wrap()
+        .query_wasm_smart(
+            token_addr,
+            &Cw20QueryMsg::Balance {
+                address: address.to_string(),
+            },
+        )
+        .unwrap();
+    balance.balance.u128()
+}
+
+#[test]
+fn test_tie_refund_gross() {
+    let mut router = mock_app();
+
+    let (prediction_market_addr, token_addr) =
+        play_tied_round(&mut router, TiePolicy::RefundGross);
+
+    /* Only the burn isn't refunded */
+    assert_eq!(query_cw20_balance(&router, &token_addr, "user1"), 999);
+    assert_eq!(query_cw20_balance(&router, &token_addr, "user2"), 1000);
+
+    let global_stats: GlobalStats = router
+        .wrap()
+        .query_wasm_smart(prediction_market_addr, &QueryMsg::GlobalStats {})
+        .unwrap();
+    assert_eq!(global_stats.total_fees_accrued, Uint128::zero());
+    assert_eq!(global_stats.total_paid_out, Uint128::new(149));
+}
+
+#[test]
+fn test_tie_house_takes() {
+    let mut router = mock_app();
+
+    let (prediction_market_addr, token_addr) =
+        play_tied_round(&mut router, TiePolicy::HouseTakes);
+
+    assert_eq!(query_cw20_balance(&router, &token_addr, "user1"), 900);
+    assert_eq!(query_cw20_balance(&router, &token_addr, "user2"), 950);
+
+    /* The 3 of gaming fees plus the 146 pool */
+    let global_stats: GlobalStats = router
+        .wrap()
+        .query_wasm_smart(
+            prediction_market_addr.clone(),
+            &QueryMsg::GlobalStats {},
+        )
+        .unwrap();
+    assert_eq!(global_stats.total_fees_accrued, Uint128::new(149));
+    assert_eq!(global_stats.total_paid_out, Uint128::zero());
+    assert_eq!(
+        query_cw20_balance(
+            &router,
+            &token_addr,
+            prediction_market_addr.as_str()
+        ),
+        149
+    );
+}
+
+#[test]
+fn test_increase_and_withdraw_bet() {
+    let mut router = mock_app();
+    let owner = Addr::unchecked("owner");
+
+    /* 10% withdrawal penalty */
+    let default_config = Config {
+        minimum_bet: Uint128::new(50u128),
+        withdrawal_penalty: Uint128::new(1000u128),
+        ..default_config()
+    };
+
+    let prediction_market_addr =
+        create_prediction_market(&mut router, &owner, default_config);
+
+    start_next_round(&mut router, &prediction_market_addr, &owner);
+
+    let config: ConfigResponse = router
+        .wrap()
+        .query_wasm_smart(
+            prediction_market_addr.to_string(),
+            &QueryMsg::Config {},
+        )
+        .unwrap();
+
+    for _ in 0..2 {
+        execute_bet(
+            &mut router,
+            Addr::unchecked("user1"),
+            Uint128::new(100),
+            Direction::Bear,
+            &config.token_addr,
+            &prediction_market_addr,
+            Uint128::zero(),
+        );
+    }
+
+    let status: StatusResponse = router
+        .wrap()
+        .query_wasm_smart(prediction_market_addr.clone(), &QueryMsg::Status {})
+        .unwrap();
+    assert_eq!(status.bidding_round.unwrap().bear_amount, Uint128::new(194));
+
+    let withdraw_msg = |amount: u128| -> CosmosMsg {
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: prediction_market_addr.to_string(),
+            msg: to_binary(&ExecuteMsg::WithdrawBet {
+                round_id: Uint128::zero(),
+                amount: Some(Uint128::new(amount)),
+            })
+            .unwrap(),
+            funds: vec![],
+        })
+    };
+    /* 44 left would be under the minimum bet */
+    router
+        .execute_multi(Addr::unchecked("user1"), [withdraw_msg(150)].to_vec())
+        .unwrap_err();
+    router
+        .execute_multi(Addr::unchecked("user1"), [withdraw_msg(94)].to_vec())
+        .unwrap();
+
+    let status: StatusResponse = router
+        .wrap()
+        .query_wasm_smart(prediction_market_addr.clone(), &QueryMsg::Status {})
+        .unwrap();
+    assert_eq!(status.bidding_round.unwrap().bear_amount, Uint128::new(100));
+
+    let my_games: MyGameResponse = router
+        .wrap()
+        .query_wasm_smart(
+            prediction_market_addr.clone(),
+            &QueryMsg::MyGameList {
+                player: Addr::unchecked("user1"),
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(my_games.my_game_list[0].amount, Uint128::new(100));
+
+    /* 94 withdrawn less a penalty of 9 */
+    let user1_balance: BalanceResponse = router
+        .wrap()
+        .query_wasm_smart(
+            config.token_addr.to_string(),
+            &Cw20QueryMsg::Balance {
+                address: "user1".to_string(),
+            },
+        )
+        .unwrap();
+    assert_eq!(user1_balance.balance, Uint128::new(885));
+
+    /* Locked rounds can't be withdrawn from */
+    start_next_round(&mut router, &prediction_market_addr, &owner);
+    let withdraw_msg: CosmosMsg = CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: prediction_market_addr.to_string(),
+        msg: to_binary(&ExecuteMsg::WithdrawBet {
+            round_id: Uint128::zero(),
+            amount: None,
+        })
+        .unwrap(),
+        funds: vec![],
+    });
+    router
+        .execute_multi(Addr::unchecked("user1"), [withdraw_msg].to_vec())
+        .unwrap_err();
+}
+
+#[test]
+fn test_native_bet() {
+    let mut router = App::new(|router, _, storage| {
+        router
+            .bank
+            .init_balance(
+                storage,
+                &Addr::unchecked("user1"),
+                coins(1000, "ujuno"),
+            )
+            .unwrap();
+    });
+    let owner = Addr::unchecked("owner");
+
+    let default_config = Config {
+        stake_denom: Some("ujuno".to_string()),
+        ..default_config()
+    };
+
+    let prediction_market_addr =
+        create_prediction_market(&mut router, &owner, default_config);
+
+    start_next_round(&mut router, &prediction_market_addr, &owner);
+
+    /* Sent coins have to match the bet */
+    let bet_msg: CosmosMsg = CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: prediction_market_addr.to_string(),
+        msg: to_binary(&ExecuteMsg::BetBear {
+            round_id: Uint128::zero(),
+            amount: Uint128::new(100),
+            token: None,
+            referrer: None,
+        })
+        .unwrap(),
+        funds: coins(50, "ujuno"),
+    });
+    router
+        .execute_multi(Addr::unchecked("user1"), [bet_msg].to_vec())
+        .unwrap_err();
+
+    let bet_msg: CosmosMsg = CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: prediction_market_addr.to_string(),
+        msg: to_binary(&ExecuteMsg::BetBear {
+            round_id: Uint128::zero(),
+            amount: Uint128::new(100),
+            token: None,
+            referrer: None,
+        })
+        .unwrap(),
+        funds: coins(100, "ujuno"),
+    });
+    router
+        .execute_multi(Addr::unchecked("user1"), [bet_msg].to_vec())
+        .unwrap();
+
+    /* The burn fee is burned straight away */
+    let contract_balance = router
+        .wrap()
+        .query_balance(prediction_market_addr.to_string(), "ujuno")
+        .unwrap();
+    assert_eq!(contract_balance.amount, Uint128::new(99));
+
+    start_next_round(&mut router, &prediction_market_addr, &owner);
+    start_next_round(&mut router, &prediction_market_addr, &owner);
+
+    /* Nobody took the other side so the net stake is refunded */
+    let claim_msg: CosmosMsg = CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: prediction_market_addr.to_string(),
+        msg: to_binary(&ExecuteMsg::CollectWinnings {
+            round_ids: None,
+            limit: None,
+            player: None,
+            recipient: None,
+        })
+        .unwrap(),
+        funds: vec![],
+    });
+    router
+        .execute_multi(Addr::unchecked("user1"), [claim_msg].to_vec())
+        .unwrap();
+
+    let user1_balance = router
+        .wrap()
+        .query_balance("user1".to_string(), "ujuno")
+        .unwrap();
+    assert_eq!(user1_balance.amount, Uint128::new(997));
+}
+
+#[test]
+fn test_bet_through_send() {
+    let mut router = mock_app();
+    let owner = Addr::unchecked("owner");
+
+    let prediction_market_addr =
+        create_prediction_market(&mut router, &owner, default_config());
+
+    start_next_round(&mut router, &prediction_market_addr, &owner);
+
+    let config: ConfigResponse = router
+        .wrap()
+        .query_wasm_smart(
+            prediction_market_addr.to_string(),
+            &QueryMsg::Config {},
+        )
+        .unwrap();
+
+    /* No allowance needed, the bet rides along with the transfer */
+    let send_msg: CosmosMsg = CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: config.token_addr.to_string(),
+        msg: to_binary(&Cw20ExecuteMsg::Send {
+            contract: prediction_market_addr.to_string(),
+            amount: Uint128::new(100),
+            msg: to_binary(&ReceiveMsg::BetBull {
+                round_id: Uint128::zero(),
+                referrer: None,
+            })
+            .unwrap(),
+        })
+        .unwrap(),
+        funds: vec![],
+    });
+    router
+        .execute_multi(Addr::unchecked("user3"), [send_msg].to_vec())
+        .unwrap();
+
+    let status: StatusResponse = router
+        .wrap()
+        .query_wasm_smart(prediction_market_addr.clone(), &QueryMsg::Status {})
+        .unwrap();
+    assert_eq!(status.bidding_round.unwrap().bull_amount, Uint128::new(97));
+
+    /* The burn fee is taken from what the contract received */
+    let contract_balance: BalanceResponse = router
+        .wrap()
+        .query_wasm_smart(
+            config.token_addr.to_string(),
+            &Cw20QueryMsg::Balance {
+                address: prediction_market_addr.to_string(),
+            },
+        )
+        .unwrap();
+    assert_eq!(contract_balance.balance, Uint128::new(99));
+
+    /* Only the stake token can call the hook */
+    let fake_hook_msg: CosmosMsg = CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: prediction_market_addr.to_string(),
+        msg: to_binary(&ExecuteMsg::Receive(cw20::Cw20ReceiveMsg {
+            sender: "user3".to_string(),
+            amount: Uint128::new(100),
+            msg: to_binary(&ReceiveMsg::BetBull {
+                round_id: Uint128::zero(),
+                referrer: None,
+            })
+            .unwrap(),
+        }))
+        .unwrap(),
+        funds: vec![],
+    });
+    router
+        .execute_multi(Addr::unchecked("user3"), [fake_hook_msg].to_vec())
+        .unwrap_err();
+}
+
+fn send_bet(
+    router: &mut App,
+    user: &str,
+    amount: Uint128,
+    bet: ReceiveMsg,
+    token_addr: &Addr,
+    prediction_market_addr: &Addr,
+) {
+    let send_msg: CosmosMsg = CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: token_addr.to_string(),
+        msg: to_binary(&Cw20ExecuteMsg::Send {
+            contract: prediction_market_addr.to_string(),
+            amount,
+            msg: to_binary(&bet).unwrap(),
+        })
+        .unwrap(),
+        funds: vec![],
+    });
+    router
+        .execute_multi(Addr::unchecked(user), [send_msg].to_vec())
+        .unwrap();
+}
+
+#[test]
+fn test_whitelisted_token_bet() {
+    let mut router = mock_app();
+    let owner = Addr::unchecked("owner");
+
+    let prediction_market_addr =
+        create_prediction_market(&mut router, &owner, default_config());
+    let other_token_addr = init_cw20_Contract(&mut router, &owner);
+
+    start_next_round(&mut router, &prediction_market_addr, &owner);
+
+    let config: ConfigResponse = router
+        .wrap()
+        .query_wasm_smart(
+            prediction_market_addr.to_string(),
+            &QueryMsg::Config {},
+        )
+        .unwrap();
+
+    let round_id = Uint128::zero();
+
+    /* Not whitelisted yet */
+    let bet_msg: CosmosMsg = CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: other_token_addr.to_string(),
+        msg: to_binary(&Cw20ExecuteMsg::Send {
+            contract: prediction_market_addr.to_string(),
+            amount: Uint128::new(100),
+            msg: to_binary(&ReceiveMsg::BetBear {
+                round_id,
+                referrer: None,
+            })
+            .unwrap(),
+        })
+        .unwrap(),
+        funds: vec![],
+    });
+    router
+        .execute_multi(Addr::unchecked("user1"), [bet_msg].to_vec())
+        .unwrap_err();
+
+    /* 5% gaming fee and no burn, bets of up to 100 */
+    let whitelist_msg: CosmosMsg = CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: prediction_market_addr.to_string(),
+        msg: to_binary(&ExecuteMsg::SetStakeToken {
+            token: StakeToken {
+                asset: AssetInfo::Token {
+                    contract_addr: other_token_addr.clone(),
+                },
+                minimum_bet: Uint128::new(10u128),
+                burn_fee: Uint128::zero(),
+                gaming_fee: Uint128::new(500u128),
+                maximum_bet: Uint128::new(100u128),
+                maximum_position: Uint128::zero(),
+                maximum_round_pool: Uint128::zero(),
+            },
+        })
+        .unwrap(),
+        funds: vec![],
+    });
+    router
+        .execute_multi(owner.clone(), [whitelist_msg].to_vec())
+        .unwrap();
+
+    let bet_msg: CosmosMsg = CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: other_token_addr.to_string(),
+        msg: to_binary(&Cw20ExecuteMsg::Send {
+            contract: prediction_market_addr.to_string(),
+            amount: Uint128::new(101),
+            msg: to_binary(&ReceiveMsg::BetBear {
+                round_id,
+                referrer: None,
+            })
+            .unwrap(),
+        })
+        .unwrap(),
+        funds: vec![],
+    });
+    router
+        .execute_multi(Addr::unchecked("user1"), [bet_msg].to_vec())
+        .unwrap_err();
+
+    send_bet(
+        &mut router,
+        "user1",
+        Uint128::new(100),
+        ReceiveMsg::BetBear {
+            round_id,
+            referrer: None,
+        },
+        &other_token_addr,
+        &prediction_market_addr,
+    );
+    send_bet(
+        &mut router,
+        "user2",
+        Uint128::new(100),
+        ReceiveMsg::BetBull {
+            round_id,
+            referrer: None,
+        },
+        &other_token_addr,
+        &prediction_market_addr,
+    );
+    execute_bet(
+        &mut router,
+        Addr::unchecked("user3"),
+        Uint128::new(100),
+        Direction::Bull,
+        &config.token_addr,
+        &prediction_market_addr,
+        round_id,
+    );
+
+    /* Pools never mix */
+    let status: StatusResponse = router
+        .wrap()
+        .query_wasm_smart(prediction_market_addr.clone(), &QueryMsg::Status {})
+        .unwrap();
+    let bidding_round = status.bidding_round.unwrap();
+    assert_eq!(bidding_round.bull_amount, Uint128::new(97));
+    assert_eq!(bidding_round.bear_amount, Uint128::zero());
+
+    let round_pools: RoundPoolsResponse = router
+        .wrap()
+        .query_wasm_smart(
+            prediction_market_addr.clone(),
+            &QueryMsg::RoundPools { round_id },
+        )
+        .unwrap();
+    assert_eq!(
+        round_pools.pools,
+        vec![(
+            other_token_addr.to_string(),
+            RoundPool {
+                bull_amount: Uint128::new(95),
+                bear_amount: Uint128::new(95),
+                fee_amount: Uint128::new(10),
+            }
+        )]
+    );
+
+    start_next_round(&mut router, &prediction_market_addr, &owner);
+    update_price(&mut router, config, Uint128::new(1_100_000), &owner);
+    start_next_round(&mut router, &prediction_market_addr, &owner);
+
+    settle_round(&mut router, &prediction_market_addr, round_id);
+    let pending_reward_user2: PendingRewardResponse = router
+        .wrap()
+        .query_wasm_smart(
+            prediction_market_addr.clone(),
+            &QueryMsg::MyPendingReward {
+                player: Addr::unchecked("user2"),
+            },
+        )
+        .unwrap();
+    assert_eq!(pending_reward_user2.pending_reward, Uint128::zero());
+    assert_eq!(
+        pending_reward_user2.token_rewards,
+        vec![AssetAmount {
+            info: AssetInfo::Token {
+                contract_addr: other_token_addr.clone(),
+            },
+            amount: Uint128::new(190),
+        }]
+    );
+
+    /* The primary pool was one sided and is refunded */
+    let pending_reward_user3: PendingRewardResponse = router
+        .wrap()
+        .query_wasm_smart(
+            prediction_market_addr.clone(),
+            &QueryMsg::MyPendingReward {
+                player: Addr::unchecked("user3"),
+            },
+        )
+        .unwrap();
+    assert_eq!(pending_reward_user3.pending_reward, Uint128::new(97));
+
+    let claim_msg: CosmosMsg = CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: prediction_market_addr.to_string(),
+        msg: to_binary(&ExecuteMsg::CollectWinnings {
+            round_ids: None,
+            limit: None,
+            player: None,
+            recipient: None,
+        })
+        .unwrap(),
+        funds: vec![],
+    });
+    router
+        .execute_multi(Addr::unchecked("user2"), [claim_msg].to_vec())
+        .unwrap();
+
+    let user2_balance: BalanceResponse = router
+        .wrap()
+        .query_wasm_smart(
+            other_token_addr.to_string(),
+            &Cw20QueryMsg::Balance {
+                address: "user2".to_string(),
+            },
+        )
+        .unwrap();
+    assert_eq!(user2_balance.balance, Uint128::new(1090));
+}
+
+#[test]
+fn test_tie_after_token_removed() {
+    let mut router = mock_app();
+    let owner = Addr::unchecked("owner");
+
+    let prediction_market_addr = create_prediction_market(
+        &mut router,
+        &owner,
+        Config {
+            tie_policy: TiePolicy::HouseTakes,
+            ..default_config()
+        },
+    );
+    let other_token_addr = init_cw20_Contract(&mut router, &owner);
+    let other_asset = AssetInfo::Token {
+        contract_addr: other_token_addr.clone(),
+    };
+
+    start_next_round(&mut router, &prediction_market_addr, &owner);
+
+    let config: ConfigResponse = router
+        .wrap()
+        .query_wasm_smart(
+            prediction_market_addr.to_string(),
+            &QueryMsg::Config {},
+        )
+        .unwrap();
+
+    let admin_msgs: Vec<CosmosMsg> = vec![
+        ExecuteMsg::SetStakeToken {
+            token: StakeToken {
+                asset: other_asset.clone(),
+                minimum_bet: Uint128::new(10u128),
+                burn_fee: Uint128::zero(),
+                gaming_fee: Uint128::new(500u128),
+                maximum_bet: Uint128::zero(),
+                maximum_position: Uint128::zero(),
+                maximum_round_pool: Uint128::zero(),
+            },
+        },
+        ExecuteMsg::SetFeeRecipients {
+            recipients: vec![WalletInfo {
+                address: Addr::unchecked("admin1"),
+                ratio: Decimal::one(),
+            }],
+        },
+    ]
+    .into_iter()
+    .map(|msg| {
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: prediction_market_addr.to_string(),
+            msg: to_binary(&msg).unwrap(),
+            funds: vec![],
+        })
+    })
+    .collect();
+    router.execute_multi(owner.clone(), admin_msgs).unwrap();
+
+    let round_id = Uint128::zero();
+    send_bet(
+        &mut router,
+        "user1",
+        Uint128::new(100),
+        ReceiveMsg::BetBear {
+            round_id,
+            referrer: None,
+        },
+        &other_token_addr,
+        &prediction_market_addr,
+    );
+    send_bet(
+        &mut router,
+        "user2",
+        Uint128::new(100),
+        ReceiveMsg::BetBull {
+            round_id,
+            referrer: None,
+        },
+        &other_token_addr,
+        &prediction_market_addr,
+    );
+    for (user, direction) in
+        [("user3", Direction::Bull), ("user4", Direction::Bear)]
+    {
+        execute_bet(
+            &mut router,
+            Addr::unchecked(user),
+            Uint128::new(100),
+            direction,
+            &config.token_addr,
+            &prediction_market_addr,
+            round_id,
+        );
+    }
+
+    let remove_msg: CosmosMsg = CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: prediction_market_addr.to_string(),
+        msg: to_binary(&ExecuteMsg::RemoveStakeToken { asset: other_asset })
+            .unwrap(),
+        funds: vec![],
+    });
+    router
+        .execute_multi(owner.clone(), [remove_msg].to_vec())
+        .unwrap();
+
+    start_next_round(&mut router, &prediction_market_addr, &owner);
+    start_next_round(&mut router, &prediction_market_addr, &owner);
+
+    /* Each pool goes to the house exactly once, fees included */
+    let global_stats: GlobalStats = router
+        .wrap()
+        .query_wasm_smart(
+            prediction_market_addr.clone(),
+            &QueryMsg::GlobalStats {},
+        )
+        .unwrap();
+    assert_eq!(global_stats.total_fees_accrued, Uint128::new(198));
+
+    let distribute_msg: CosmosMsg = CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: prediction_market_addr.to_string(),
+        msg: to_binary(&ExecuteMsg::DistributeFund {}).unwrap(),
+        funds: vec![],
+    });
+    router
+        .execute_multi(owner, [distribute_msg].to_vec())
+        .unwrap();
+
+    assert_eq!(
+        query_cw20_balance(&router, &config.token_addr, "admin1"),
+        198
+    );
+    assert_eq!(
+        query_cw20_balance(&router, &other_token_addr, "admin1"),
+        200
+    );
+}
+
+#[test]
+fn test_collect_winnings_skips_active_rounds() {
+    let mut router = mock_app();
+    let owner = Addr::unchecked("owner");
+
+    let prediction_market_addr =
+        create_prediction_market(&mut router, &owner, default_config());
+
+    start_next_round(&mut router, &prediction_market_addr, &owner);
+
+    let config: ConfigResponse = router
+        .wrap()
+        .query_wasm_smart(
+            prediction_market_addr.to_string(),
+            &QueryMsg::Config {},
+        )
+        .unwrap();
+
+    execute_bet(
+        &mut router,
+        Addr::unchecked("user1"),
+        Uint128::new(100),
+        Direction::Bear,
+        &config.token_addr,
+        &prediction_market_addr,
+        Uint128::zero(),
+    );
+    start_next_round(&mut router, &prediction_market_addr, &owner);
+    execute_bet(
+        &mut router,
+        Addr::unchecked("user1"),
+        Uint128::new(100),
+        Direction::Bear,
+        &config.token_addr,
+        &prediction_market_addr,
+        Uint128::new(1),
+    );
+    start_next_round(&mut router, &prediction_market_addr, &owner);
+
+    /* Round 0 is finished while round 1 is live */
+    let claim_msg: CosmosMsg = CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: prediction_market_addr.to_string(),
+        msg: to_binary(&ExecuteMsg::CollectWinnings {
+            round_ids: None,
+            limit: Some(1),
+            player: None,
+            recipient: None,
+        })
+        .unwrap(),
+        funds: vec![],
+    });
+    router
+        .execute_multi(Addr::unchecked("user1"), [claim_msg.clone()].to_vec())
+        .unwrap();
+
+    let user1_balance: BalanceResponse = router
+        .wrap()
+        .query_wasm_smart(
+            config.token_addr.to_string(),
+            &Cw20QueryMsg::Balance {
+                address: "user1".to_string(),
+            },
+        )
+        .unwrap();
+    assert_eq!(user1_balance.balance, Uint128::new(897));
+
+    let my_games: MyGameResponse = router
+        .wrap()
+        .query_wasm_smart(
+            prediction_market_addr.clone(),
+            &QueryMsg::MyGameList {
+                player: Addr::unchecked("user1"),
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(my_games.my_game_list.len(), 1);
+    assert_eq!(my_games.my_game_list[0].round_id, Uint128::new(1));
+
+    /* The live bet can't be settled yet */
+    router
+        .execute_multi(Addr::unchecked("user1"), [claim_msg].to_vec())
+        .unwrap_err();
+}
+
+#[test]
+fn test_claim_operator() {
+    let mut router = mock_app();
+    let owner = Addr::unchecked("owner");
+
+    let prediction_market_addr =
+        create_prediction_market(&mut router, &owner, default_config());
+
+    start_next_round(&mut router, &prediction_market_addr, &owner);
+
+    let config: ConfigResponse = router
+        .wrap()
+        .query_wasm_smart(
+            prediction_market_addr.to_string(),
+            &QueryMsg::Config {},
+        )
+        .unwrap();
+
+    execute_bet(
+        &mut router,
+        Addr::unchecked("user1"),
+        Uint128::new(100),
+        Direction::Bear,
+        &config.token_addr,
+        &prediction_market_addr,
+        Uint128::zero(),
+    );
+    start_next_round(&mut router, &prediction_market_addr, &owner);
+    start_next_round(&mut router, &prediction_market_addr, &owner);
+
+    let claim_for_user1 = |recipient: Option<String>| -> CosmosMsg {
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: prediction_market_addr.to_string(),
+            msg: to_binary(&ExecuteMsg::CollectWinnings {
+                round_ids: None,
+                limit: None,
+                player: Some("user1".to_string()),
+                recipient,
+            })
+            .unwrap(),
+            funds: vec![],
+        })
+    };
+
+    /* Not an operator yet */
+    router
+        .execute_multi(
+            Addr::unchecked("keeper"),
+            [claim_for_user1(None)].to_vec(),
+        )
+        .unwrap_err();
+
+    let allow_msg: CosmosMsg = CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: prediction_market_addr.to_string(),
+        msg: to_binary(&ExecuteMsg::AllowClaimOperator {
+            operator: "keeper".to_string(),
+        })
+        .unwrap(),
+        funds: vec![],
+    });
+    router
+        .execute_multi(Addr::unchecked("user1"), [allow_msg].to_vec())
+        .unwrap();
+
+    /* Operators can't pay out to themselves */
+    router
+        .execute_multi(
+            Addr::unchecked("keeper"),
+            [claim_for_user1(Some("keeper".to_string()))].to_vec(),
+        )
+        .unwrap_err();
+
+    router
+        .execute_multi(
+            Addr::unchecked("keeper"),
+            [claim_for_user1(None)].to_vec(),
+        )
+        .unwrap();
+
+    let user1_balance: BalanceResponse = router
+        .wrap()
+        .query_wasm_smart(
+            config.token_addr.to_string(),
+            &Cw20QueryMsg::Balance {
+                address: "user1".to_string(),
+            },
+        )
+        .unwrap();
+    assert_eq!(user1_balance.balance, Uint128::new(997));
+}
+
+#[test]
+fn test_settle_round() {
+    let mut router = mock_app();
+    let owner = Addr::unchecked("owner");
+
+    let default_config = Config {
+        push_settlement: true,
+        ..default_config()
+    };
+
+    let prediction_market_addr =
+        create_prediction_market(&mut router, &owner, default_config);
+
+    start_next_round(&mut router, &prediction_market_addr, &owner);
+
+    let config: ConfigResponse = router
+        .wrap()
+        .query_wasm_smart(
+            prediction_market_addr.to_string(),
+            &QueryMsg::Config {},
+        )
+        .unwrap();
+
+    execute_bet(
+        &mut router,
+        Addr::unchecked("user1"),
+        Uint128::new(100),
+        Direction::Bear,
+        &config.token_addr,
+        &prediction_market_addr,
+        Uint128::zero(),
+    );
+    execute_bet(
+        &mut router,
+        Addr::unchecked("user2"),
+        Uint128::new(100),
+        Direction::Bull,
+        &config.token_addr,
+        &prediction_market_addr,
+        Uint128::zero(),
+    );
+
+    start_next_round(&mut router, &prediction_market_addr, &owner);
+    update_price(&mut router, config.clone(), Uint128::new(1_100_000), &owner);
+    start_next_round(&mut router, &prediction_market_addr, &owner);
+
+    /* Betting again leaves the finished bet to the keeper */
+    execute_bet(
+        &mut router,
+        Addr::unchecked("user2"),
+        Uint128::new(100),
+        Direction::Bull,
+        &config.token_addr,
+        &prediction_market_addr,
+        Uint128::new(2),
+    );
+
+    let settle_msg: CosmosMsg = CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: prediction_market_addr.to_string(),
+        msg: to_binary(&ExecuteMsg::SettleRound {
+            round_id: Uint128::zero(),
+            limit: Some(1),
+        })
+        .unwrap(),
+        funds: vec![],
+    });
+
+    /* user1 lost, user2 is paid on the second page */
+    for _ in 0..2 {
+        router
+            .execute_multi(
+                Addr::unchecked("keeper"),
+                [settle_msg.clone()].to_vec(),
+            )
+            .unwrap();
+    }
+
+    let user2_balance: BalanceResponse = router
+        .wrap()
+        .query_wasm_smart(
+            config.token_addr.to_string(),
+            &Cw20QueryMsg::Balance {
+                address: "user2".to_string(),
+            },
+        )
+        .unwrap();
+    assert_eq!(user2_balance.balance, Uint128::new(994));
+
+    /* Nothing is left to claim */
+    let claim_msg: CosmosMsg = CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: prediction_market_addr.to_string(),
+        msg: to_binary(&ExecuteMsg::CollectWinnings {
+            round_ids: None,
+            limit: None,
+            player: None,
+            recipient: None,
+        })
+        .unwrap(),
+        funds: vec![],
+    });
+    router
+        .execute_multi(Addr::unchecked("user2"), [claim_msg].to_vec())
+        .unwrap_err();
+}
+
+#[test]
+fn test_claimable_ledger() {
+    let mut router = mock_app();
+    let owner = Addr::unchecked("owner");
+
+    let prediction_market_addr =
+        create_prediction_market(&mut router, &owner, default_config());
+
+    start_next_round(&mut router, &prediction_market_addr, &owner);
+
+    let config: ConfigResponse = router
+        .wrap()
+        .query_wasm_smart(
+            prediction_market_addr.to_string(),
+            &QueryMsg::Config {},
+        )
+        .unwrap();
+
+    execute_bet(
+        &mut router,
+        Addr::unchecked("user1"),
+        Uint128::new(100),
+        Direction::Bear,
+        &config.token_addr,
+        &prediction_market_addr,
+        Uint128::zero(),
+    );
+    start_next_round(&mut router, &prediction_market_addr, &owner);
+    start_next_round(&mut router, &prediction_market_addr, &owner);
+
+    /* Finished but not settled yet */
+    let pending_reward_user1: PendingRewardResponse = router
+        .wrap()
+        .query_wasm_smart(
+            prediction_market_addr.clone(),
+            &QueryMsg::MyPendingReward {
+                player: Addr::unchecked("user1"),
+            },
+        )
+        .unwrap();
+    assert_eq!(pending_reward_user1.pending_reward, Uint128::zero());
+
+    /* Betting again resolves the finished round 0 */
+    execute_bet(
+        &mut router,
+        Addr::unchecked("user1"),
+        Uint128::new(100),
+        Direction::Bear,
+        &config.token_addr,
+        &prediction_market_addr,
+        Uint128::new(2),
+    );
+
+    let my_games: MyGameResponse = router
+        .wrap()
+        .query_wasm_smart(
+            prediction_market_addr.clone(),
+            &QueryMsg::MyGameList {
+                player: Addr::unchecked("user1"),
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(my_games.my_game_list.len(), 1);
+    assert_eq!(my_games.my_game_list[0].round_id, Uint128::new(2));
+
+    let pending_reward_user1: PendingRewardResponse = router
+        .wrap()
+        .query_wasm_smart(
+            prediction_market_addr.clone(),
+            &QueryMsg::MyPendingReward {
+                player: Addr::unchecked("user1"),
+            },
+        )
+        .unwrap();
+    assert_eq!(pending_reward_user1.pending_reward, Uint128::new(97));
+
+    /* The claim pays the ledger out while round 2 is still open */
+    let claim_msg: CosmosMsg = CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: prediction_market_addr.to_string(),
+        msg: to_binary(&ExecuteMsg::CollectWinnings {
+            round_ids: None,
+            limit: None,
+            player: None,
+            recipient: None,
+        })
+        .unwrap(),
+        funds: vec![],
+    });
+    router
+        .execute_multi(Addr::unchecked("user1"), [claim_msg].to_vec())
+        .unwrap();
+
+    let user1_balance: BalanceResponse = router
+        .wrap()
+        .query_wasm_smart(
+            config.token_addr.to_string(),
+            &Cw20QueryMsg::Balance {
+                address: "user1".to_string(),
+            },
+        )
+        .unwrap();
+    assert_eq!(user1_balance.balance, Uint128::new(897));
+}
+
+#[test]
+fn test_transfer_bet() {
+    let mut router = mock_app();
+    let owner = Addr::unchecked("owner");
+
+    let prediction_market_addr =
+        create_prediction_market(&mut router, &owner, default_config());
+
+    start_next_round(&mut router, &prediction_market_addr, &owner);
+
+    let config: ConfigResponse = router
+        .wrap()
+        .query_wasm_smart(
+            prediction_market_addr.to_string(),
+            &QueryMsg::Config {},
+        )
+        .unwrap();
+
+    for user in ["user1", "user3"] {
+        execute_bet(
+            &mut router,
+            Addr::unchecked(user),
+            Uint128::new(100),
+            Direction::Bear,
+            &config.token_addr,
+            &prediction_market_addr,
+            Uint128::zero(),
+        );
+    }
+
+    let transfer_to = |recipient: &str| -> CosmosMsg {
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: prediction_market_addr.to_string(),
+            msg: to_binary(&ExecuteMsg::TransferBet {
+                round_id: Uint128::zero(),
+                recipient: recipient.to_string(),
+            })
+            .unwrap(),
+            funds: vec![],
+        })
+    };
+
+    /* user3 already has a bet in the round */
+    router
+        .execute_multi(
+            Addr::unchecked("user1"),
+            [transfer_to("user3")].to_vec(),
+        )
+        .unwrap_err();
+    router
+        .execute_multi(
+            Addr::unchecked("user1"),
+            [transfer_to("user2")].to_vec(),
+        )
+        .unwrap();
+
+    let my_games = |router: &App, player: &str| -> MyGameResponse {
+        router
+            .wrap()
+            .query_wasm_smart(
+                prediction_market_addr.clone(),
+                &QueryMsg::MyGameList {
+                    player: Addr::unchecked(player),
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap()
+    };
+    assert!(my_games(&router, "user1").my_game_list.is_empty());
+    let user2_games = my_games(&router, "user2").my_game_list;
+    assert_eq!(user2_games.len(), 1);
+    assert_eq!(user2_games[0].player, Addr::unchecked("user2"));
+    assert_eq!(user2_games[0].amount, Uint128::new(97));
+}
+
+#[test]
+fn test_position_nft() {
+    let mut router = mock_app();
+    let owner = Addr::unchecked("owner");
+
+    let nft_code_id = router.store_code(contract_position_nft());
+    let nft_addr = router
+        .instantiate_contract(
+            nft_code_id,
+            owner.clone(),
+            &Empty {},
+            &[],
+            "positions",
+            None,
+        )
+        .unwrap();
+
+    let prediction_market_addr = create_prediction_market(
+        &mut router,
+        &owner,
+        Config {
+            push_settlement: true,
+            position_nft_addr: Some(nft_addr.clone()),
+            ..default_config()
+        },
+    );
+
+    start_next_round(&mut router, &prediction_market_addr, &owner);
+
+    let config: ConfigResponse = router
+        .wrap()
+        .query_wasm_smart(
+            prediction_market_addr.to_string(),
+            &QueryMsg::Config {},
+        )
+        .unwrap();
+
+    let round_id = Uint128::zero();
+    for (user, direction) in [
+        ("user1", Direction::Bear),
+        ("user2", Direction::Bull),
+        ("user4", Direction::Bear),
+    ] {
+        execute_bet(
+            &mut router,
+            Addr::unchecked(user),
+            Uint128::new(100),
+            direction,
+            &config.token_addr,
+            &prediction_market_addr,
+            round_id,
+        );
+    }
+
+    let position_owner = |router: &App, token_id: &str| -> StdResult<String> {
+        router
+            .wrap()
+            .query_wasm_smart(
+                nft_addr.clone(),
+                &PositionNftQueryMsg::OwnerOf {
+                    token_id: token_id.to_string(),
+                    include_expired: None,
+                },
+            )
+            .map(|res: OwnerOfResponse| res.owner)
+    };
+    assert_eq!(position_owner(&router, "0-user1").unwrap(), "user1");
+
+    let market_msg = |msg: ExecuteMsg| -> CosmosMsg {
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: prediction_market_addr.to_string(),
+            msg: to_binary(&msg).unwrap(),
+            funds: vec![],
+        })
+    };
+
+    /* Tokenized bets can't be topped up, withdrawn or transferred */
+    for msg in [
+        ExecuteMsg::BetBear {
+            amount: Uint128::new(100),
+            round_id,
+            token: None,
+            referrer: None,
+        },
+        ExecuteMsg::WithdrawBet {
+            round_id,
+            amount: None,
+        },
+        ExecuteMsg::TransferBet {
+            round_id,
+            recipient: "user3".to_string(),
+        },
+    ] {
+        router
+            .execute_multi(Addr::unchecked("user1"), [market_msg(msg)].to_vec())
+            .unwrap_err();
+    }
+
+    /* Selling the position moves its payout */
+    let transfer_msg: CosmosMsg = CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: nft_addr.to_string(),
+        msg: to_binary(&MockNftExecuteMsg::TransferNft {
+            recipient: "user3".to_string(),
+            token_id: "0-user1".to_string(),
+        })
+        .unwrap(),
+        funds: vec![],
+    });
+    router
+        .execute_multi(Addr::unchecked("user1"), [transfer_msg].to_vec())
+        .unwrap();
+
+    let send_position_msg = |token_id: &str| -> CosmosMsg {
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: nft_addr.to_string(),
+            msg: to_binary(&MockNftExecuteMsg::SendNft {
+                contract: prediction_market_addr.to_string(),
+                token_id: token_id.to_string(),
+                msg: Binary::default(),
+            })
+            .unwrap(),
+            funds: vec![],
+        })
+    };
+    /* Round 0 hasn't finished, the position stays with its holder */
+    router
+        .execute_multi(
+            Addr::unchecked("user3"),
+            [send_position_msg("0-user1")].to_vec(),
+        )
+        .unwrap_err();
+    assert_eq!(position_owner(&router, "0-user1").unwrap(), "user3");
+
+    start_next_round(&mut router, &prediction_market_addr, &owner);
+    update_price(&mut router, config.clone(), Uint128::new(900_000), &owner);
+    start_next_round(&mut router, &prediction_market_addr, &owner);
+
+    /* The position isn't the player's to claim anymore */
+    let claim_msg = market_msg(ExecuteMsg::CollectWinnings {
+        round_ids: None,
+        limit: None,
+        player: None,
+        recipient: None,
+    });
+    router
+        .execute_multi(Addr::unchecked("user1"), [claim_msg].to_vec())
+        .unwrap_err();
+
+    router
+        .execute_multi(
+            Addr::unchecked("user1"),
+            [send_position_msg("0-user1")].to_vec(),
+        )
+        .unwrap_err();
+    /* Only the position contract hands positions in */
+    let receive_msg = market_msg(ExecuteMsg::ReceiveNft(Cw721ReceiveMsg {
+        sender: "user1".to_string(),
+        token_id: "0-user4".to_string(),
+        msg: Binary::default(),
+    }));
+    router
+        .execute_multi(Addr::unchecked("user1"), [receive_msg].to_vec())
+        .unwrap_err();
+    router
+        .execute_multi(
+            Addr::unchecked("user3"),
+            [send_position_msg("0-user1")].to_vec(),
+        )
+        .unwrap();
+
+    /* 291 shared by the two Bear positions of 97 */
+    assert_eq!(
+        query_cw20_balance(&router, &config.token_addr, "user3"),
+        1145
+    );
+    position_owner(&router, "0-user1").unwrap_err();
+
+    /* The keeper settles the rest, kept until their holders send them in */
+    let settle_msg = market_msg(ExecuteMsg::SettleRound {
+        round_id,
+        limit: None,
+    });
+    router
+        .execute_multi(Addr::unchecked("keeper"), [settle_msg].to_vec())
+        .unwrap();
+    assert_eq!(
+        query_cw20_balance(&router, &config.token_addr, "user4"),
+        900
+    );
+    assert_eq!(position_owner(&router, "0-user4").unwrap(), "user4");
+
+    for user in ["user2", "user4"] {
+        router
+            .execute_multi(
+                Addr::unchecked(user),
+                [send_position_msg(&format!("0-{}", user))].to_vec(),
+            )
+            .unwrap();
+    }
+    assert_eq!(
+        query_cw20_balance(&router, &config.token_addr, "user4"),
+        1045
+    );
+    assert_eq!(
+        query_cw20_balance(&router, &config.token_addr, "user2"),
+        900
+    );
+    position_owner(&router, "0-user2").unwrap_err();
+    position_owner(&router, "0-user4").unwrap_err();
+}
+
+#[test]
+fn test_bucket_round() {
+    let mut router = mock_app();
+    let owner = Addr::unchecked("owner");
+
+    /* < -1%, -1%..0, 0..1%, > 1% */
+    let default_config = Config {
+        bucket_bounds_bps: vec![-100, 0, 100],
+        ..default_config()
+    };
+
+    let prediction_market_addr =
+        create_prediction_market(&mut router, &owner, default_config);
+
+    start_next_round(&mut router, &prediction_market_addr, &owner);
+
+    let config: ConfigResponse = router
+        .wrap()
+        .query_wasm_smart(
+            prediction_market_addr.to_string(),
+            &QueryMsg::Config {},
+        )
+        .unwrap();
+
+    let round_id = Uint128::zero();
+    for (user, bucket) in [("user1", 3u8), ("user2", 2u8), ("user3", 0u8)] {
+        send_bet(
+            &mut router,
+            user,
+            Uint128::new(100),
+            ReceiveMsg::BetBucket { round_id, bucket },
+            &config.token_addr,
+            &prediction_market_addr,
+        );
+    }
+
+    /* Range rounds don't take bull/bear bets */
+    let bull_msg: CosmosMsg = CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: config.token_addr.to_string(),
+        msg: to_binary(&Cw20ExecuteMsg::Send {
+            contract: prediction_market_addr.to_string(),
+            amount: Uint128::new(100),
+            msg: to_binary(&ReceiveMsg::BetBull {
+                round_id,
+                referrer: None,
+            })
+            .unwrap(),
+        })
+        .unwrap(),
+        funds: vec![],
+    });
+    router
+        .execute_multi(Addr::unchecked("user4"), [bull_msg].to_vec())
+        .unwrap_err();
+
+    let round_buckets: RoundBucketsResponse = router
+        .wrap()
+        .query_wasm_smart(
+            prediction_market_addr.clone(),
+            &QueryMsg::RoundBuckets { round_id },
+        )
+        .unwrap();
+    assert_eq!(
+        round_buckets.pools,
+        vec![
+            Uint128::new(97),
+            Uint128::zero(),
+            Uint128::new(97),
+            Uint128::new(97)
+        ]
+    );
+
+    /* A 5% move falls in the last bucket */
+    start_next_round(&mut router, &prediction_market_addr, &owner);
+    update_price(&mut router, config.clone(), Uint128::new(1_050_000), &owner);
+    start_next_round(&mut router, &prediction_market_addr, &owner);
+
+    let round: FinishedRound = router
+        .wrap()
+        .query_wasm_smart(
+            prediction_market_addr.clone(),
+            &QueryMsg::FinishedRound { round_id },
+        )
+        .unwrap();
+    assert_eq!(round.winning_bucket, Some(3));
+
+    settle_round(&mut router, &prediction_market_addr, round_id);
+    for (user, reward) in [("user1", 291u128), ("user2", 0u128)] {
+        let pending_reward: PendingRewardResponse = router
+            .wrap()
+            .query_wasm_smart(
+                prediction_market_addr.clone(),
+                &QueryMsg::MyPendingReward {
+                    player: Addr::unchecked(user),
+                },
+            )
+            .unwrap();
+        assert_eq!(pending_reward.pending_reward, Uint128::new(reward));
+    }
+
+    /* Drops of 0.1 and 100.5 bps aren't rounded up into the bucket above */
+    for (round_id, price, bucket) in
+        [(1u128, 1_049_990u128, 1u8), (2u128, 1_039_437u128, 0u8)]
+    {
+        update_price(&mut router, config.clone(), Uint128::new(price), &owner);
+        start_next_round(&mut router, &prediction_market_addr, &owner);
+        let round: FinishedRound = router
+            .wrap()
+            .query_wasm_smart(
+                prediction_market_addr.clone(),
+                &QueryMsg::FinishedRound {
+                    round_id: Uint128::new(round_id),
+                },
+            )
+            .unwrap();
+        assert_eq!(round.winning_bucket, Some(bucket));
+    }
+}
+
+#[test]
+fn test_strike_market() {
+    let mut router = mock_app();
+    let owner = Addr::unchecked("owner");
+
+    let prediction_market_addr =
+        create_prediction_market(&mut router, &owner, default_config());
+
+    let config: ConfigResponse = router
+        .wrap()
+        .query_wasm_smart(
+            prediction_market_addr.to_string(),
+            &QueryMsg::Config {},
+        )
+        .unwrap();
+
+    let now = router.block_info().time;
+    let create_msg: CosmosMsg = CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: prediction_market_addr.to_string(),
+        msg: to_binary(&ExecuteMsg::CreateStrikeMarket {
+            strike_price: Uint128::new(1_050_000),
+            cutoff_time: now.plus_seconds(100),
+            expiry_time: now.plus_seconds(200),
+        })
+        .unwrap(),
+        funds: vec![],
+    });
+
+    /* Only the admin or an allowed creator */
+    router
+        .execute_multi(Addr::unchecked("user1"), [create_msg.clone()].to_vec())
+        .unwrap_err();
+    router
+        .execute_multi(owner.clone(), [create_msg].to_vec())
+        .unwrap();
+
+    let market_id = Uint128::new(STRIKE_MARKET_ID_OFFSET);
+    execute_bet(
+        &mut router,
+        Addr::unchecked("user1"),
+        Uint128::new(100),
+        Direction::Bull,
+        &config.token_addr,
+        &prediction_market_addr,
+        market_id,
+    );
+    execute_bet(
+        &mut router,
+        Addr::unchecked("user2"),
+        Uint128::new(100),
+        Direction::Bear,
+        &config.token_addr,
+        &prediction_market_addr,
+        market_id,
+    );
+
+    let settle_msg: CosmosMsg = CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: prediction_market_addr.to_string(),
+        msg: to_binary(&ExecuteMsg::SettleStrikeMarket { market_id }).unwrap(),
+        funds: vec![],
+    });
+
+    /* Not expired yet */
+    router
+        .execute_multi(Addr::unchecked("keeper"), [settle_msg.clone()].to_vec())
+        .unwrap_err();
+
+    router.update_block(|block| {
+        block.time = block.time.plus_seconds(200);
+        block.height += 1;
+    });
+    update_price(&mut router, config, Uint128::new(1_100_000), &owner);
+    /* Nobody else picks the price it settles at */
+    router
+        .execute_multi(Addr::unchecked("keeper"), [settle_msg.clone()].to_vec())
+        .unwrap_err();
+    router
+        .execute_multi(owner.clone(), [settle_msg].to_vec())
+        .unwrap();
+
+    let round: FinishedRound = router
+        .wrap()
+        .query_wasm_smart(
+            prediction_market_addr.clone(),
+            &QueryMsg::FinishedRound {
+                round_id: market_id,
+            },
+        )
+        .unwrap();
+    assert_eq!(round.winner, Some(Direction::Bull));
+
+    settle_round(&mut router, &prediction_market_addr, market_id);
+    let pending_reward_user1: PendingRewardResponse = router
+        .wrap()
+        .query_wasm_smart(
+            prediction_market_addr,
+            &QueryMsg::MyPendingReward {
+                player: Addr::unchecked("user1"),
+            },
+        )
+        .unwrap();
+    assert_eq!(pending_reward_user1.pending_reward, Uint128::new(194));
+}
+
+#[test]
+fn test_late_strike_settlement() {
+    let mut router = mock_app();
+    let owner = Addr::unchecked("owner");
+
+    let prediction_market_addr =
+        create_prediction_market(&mut router, &owner, default_config());
+
+    let config: ConfigResponse = router
+        .wrap()
+        .query_wasm_smart(
+            prediction_market_addr.to_string(),
+            &QueryMsg::Config {},
+        )
+        .unwrap();
+
+    let now = router.block_info().time;
+    let create_msg: CosmosMsg = CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: prediction_market_addr.to_string(),
+        msg: to_binary(&ExecuteMsg::CreateStrikeMarket {
+            strike_price: Uint128::new(1_050_000),
+            cutoff_time: now.plus_seconds(100),
+            expiry_time: now.plus_seconds(200),
+        })
+        .unwrap(),
+        funds: vec![],
+    });
+    router
+        .execute_multi(owner.clone(), [create_msg].to_vec())
+        .unwrap();
+
+    let market_id = Uint128::new(STRIKE_MARKET_ID_OFFSET);
+    for (user, direction) in
+        [("user1", Direction::Bull), ("user2", Direction::Bear)]
+    {
+        execute_bet(
+            &mut router,
+            Addr::unchecked(user),
+            Uint128::new(100),
+            direction,
+            &config.token_addr,
+            &prediction_market_addr,
+            market_id,
+        );
+    }
+
+    /* Waiting for a price that favours Bull doesn't pay */
+    router.update_block(|block| {
+        block.time = block.time.plus_seconds(300);
+        block.height += 1;
+    });
+    update_price(&mut router, config, Uint128::new(1_100_000), &owner);
+    let settle_msg: CosmosMsg = CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: prediction_market_addr.to_string(),
+        msg: to_binary(&ExecuteMsg::SettleStrikeMarket { market_id }).unwrap(),
+        funds: vec![],
+    });
+    router
+        .execute_multi(Addr::unchecked("user1"), [settle_msg].to_vec())
+        .unwrap();
+
+    let round: FinishedRound = router
+        .wrap()
+        .query_wasm_smart(
+            prediction_market_addr.clone(),
+            &QueryMsg::FinishedRound {
+                round_id: market_id,
+            },
+        )
+        .unwrap();
+    assert!(round.winner.is_none());
+    assert_eq!(round.tie_policy, TiePolicy::RefundGross);
+
+    /* Everything but the burn comes back */
+    settle_round(&mut router, &prediction_market_addr, market_id);
+    for user in ["user1", "user2"] {
+        let pending_reward: PendingRewardResponse = router
+            .wrap()
+            .query_wasm_smart(
+                prediction_market_addr.clone(),
+                &QueryMsg::MyPendingReward {
+                    player: Addr::unchecked(user),
+                },
+            )
+            .unwrap();
+        assert_eq!(pending_reward.pending_reward, Uint128::new(99));
+    }
+}
+
+#[test]
+fn test_odds_and_simulate_bet() {
+    let mut router = mock_app();
+    let owner = Addr::unchecked("owner");
+
+    let prediction_market_addr =
+        create_prediction_market(&mut router, &owner, default_config());
+
+    start_next_round(&mut router, &prediction_market_addr, &owner);
+
+    let config: ConfigResponse = router
+        .wrap()
+        .query_wasm_smart(
+            prediction_market_addr.to_string(),
+            &QueryMsg::Config {},
+        )
+        .unwrap();
+
+    execute_bet(
+        &mut router,
+        Addr::unchecked("user1"),
+        Uint128::new(100),
+        Direction::Bear,
+        &config.token_addr,
+        &prediction_market_addr,
+        Uint128::zero(),
+    );
+
+    let odds: OddsResponse = router
+        .wrap()
+        .query_wasm_smart(
+            prediction_market_addr.clone(),
+            &QueryMsg::Odds {
+                round_id: Uint128::zero(),
+            },
+        )
+        .unwrap();
+    assert_eq!(odds.bull_multiplier, None);
+    assert_eq!(odds.bear_multiplier, Some(Decimal::one()));
+
+    let simulate =
+        |player: &str, direction: Direction| -> SimulateBetResponse {
+            router
+                .wrap()
+                .query_wasm_smart(
+                    prediction_market_addr.clone(),
+                    &QueryMsg::SimulateBet {
+                        round_id: Uint128::zero(),
+                        direction,
+                        gross: Uint128::new(100),
+                        player: Some(player.to_string()),
+                    },
+                )
+                .unwrap()
+        };
+
+    let simulation = simulate("user2", Direction::Bull);
+    assert_eq!(simulation.error, None);
+    assert_eq!(simulation.burn_fee, Uint128::new(1));
+    assert_eq!(simulation.gaming_fee, Uint128::new(2));
+    assert_eq!(simulation.net_stake, Uint128::new(97));
+    assert_eq!(simulation.bull_multiplier, Some(Decimal::percent(200)));
+    assert_eq!(simulation.payout_if_win, Uint128::new(194));
+
+    /* user1 is already on the other side */
+    let simulation = simulate("user1", Direction::Bull);
+    assert!(simulation.error.is_some());
+    assert_eq!(simulation.payout_if_win, Uint128::zero());
+}
+
+#[test]
+fn test_round_history() {
+    let mut router = mock_app();
+    let owner = Addr::unchecked("owner");
+
+    let prediction_market_addr =
+        create_prediction_market(&mut router, &owner, default_config());
+
+    /* Rounds 0 to 2 finish, all without a price move */
+    for _ in 0..5 {
+        start_next_round(&mut router, &prediction_market_addr, &owner);
+    }
+
+    let finished_rounds: FinishedRoundsResponse = router
+        .wrap()
+        .query_wasm_smart(
+            prediction_market_addr.clone(),
+            &QueryMsg::FinishedRounds {
+                start_after: None,
+                limit: Some(2),
+                order: Some(OrderBy::Desc),
+            },
+        )
+        .unwrap();
+    let round_ids: Vec<Uint128> = finished_rounds
+        .rounds
+        .iter()
+        .map(|round| round.id)
+        .collect();
+    assert_eq!(round_ids, vec![Uint128::new(2), Uint128::new(1)]);
+
+    let round_at_time: RoundAtTimeResponse = router
+        .wrap()
+        .query_wasm_smart(
+            prediction_market_addr.clone(),
+            &QueryMsg::RoundAtTime {
+                timestamp: finished_rounds.rounds[1].open_time,
+            },
+        )
+        .unwrap();
+    assert_eq!(round_at_time.round.unwrap().id, Uint128::new(1));
+
+    let round_stats: RoundStats = router
+        .wrap()
+        .query_wasm_smart(prediction_market_addr, &QueryMsg::RoundStats {})
+        .unwrap();
+    assert_eq!(
+        round_stats,
+        RoundStats {
+            bull_wins: 0,
+            bear_wins: 0,
+            ties: 3,
+            range_rounds: 0,
+        }
+    );
+}
+
+#[test]
+fn test_round_bets() {
+    let mut router = mock_app();
+    let owner = Addr::unchecked("owner");
+
+    let prediction_market_addr =
+        create_prediction_market(&mut router, &owner, default_config());
+
+    start_next_round(&mut router, &prediction_market_addr, &owner);
+
+    let config: ConfigResponse = router
+        .wrap()
+        .query_wasm_smart(
+            prediction_market_addr.to_string(),
+            &QueryMsg::Config {},
+        )
+        .unwrap();
+
+    for (user, direction) in [
+        ("user1", Direction::Bull),
+        ("user2", Direction::Bear),
+        ("user3", Direction::Bull),
+    ] {
+        execute_bet(
+            &mut router,
+            Addr::unchecked(user),
+            Uint128::new(100),
+            direction,
+            &config.token_addr,
+            &prediction_market_addr,
+            Uint128::zero(),
+        );
+    }
+
+    /* A top-up does not count as a new bettor */
+    execute_bet(
+        &mut router,
+        Addr::unchecked("user1"),
+        Uint128::new(100),
+        Direction::Bull,
+        &config.token_addr,
+        &prediction_market_addr,
+        Uint128::zero(),
+    );
+
+    let withdraw_msg: CosmosMsg = CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: prediction_market_addr.to_string(),
+        msg: to_binary(&ExecuteMsg::WithdrawBet {
+            round_id: Uint128::zero(),
+            amount: None,
+        })
+        .unwrap(),
+        funds: vec![],
+    });
+    router
+        .execute_multi(Addr::unchecked("user2"), [withdraw_msg].to_vec())
+        .unwrap();
+
+    let status: StatusResponse = router
+        .wrap()
+        .query_wasm_smart(prediction_market_addr.clone(), &QueryMsg::Status {})
+        .unwrap();
+    let bidding_round = status.bidding_round.unwrap();
+    assert_eq!(bidding_round.bull_count, 2);
+    assert_eq!(bidding_round.bear_count, 0);
+
+    let round_bets: RoundBetsResponse = router
+        .wrap()
+        .query_wasm_smart(
+            prediction_market_addr.clone(),
+            &QueryMsg::RoundBets {
+                round_id: Uint128::zero(),
+                direction: Some(Direction::Bull),
+                start_after: None,
+                limit: Some(1),
+            },
+        )
+        .unwrap();
+    assert_eq!(round_bets.bets.len(), 1);
+    assert_eq!(round_bets.bets[0].player, Addr::unchecked("user1"));
+
+    let round_bets: RoundBetsResponse = router
+        .wrap()
+        .query_wasm_smart(
+            prediction_market_addr.clone(),
+            &QueryMsg::RoundBets {
+                round_id: Uint128::zero(),
+                direction: None,
+                start_after: Some("user1".to_string()),
+                limit: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(round_bets.bets.len(), 1);
+    assert_eq!(round_bets.bets[0].player, Addr::unchecked("user3"));
+
+    /* Claimed bets are still listed */
+    start_next_round(&mut router, &prediction_market_addr, &owner);
+    start_next_round(&mut router, &prediction_market_addr, &owner);
+    let claim_msg: CosmosMsg = CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: prediction_market_addr.to_string(),
+        msg: to_binary(&ExecuteMsg::CollectWinnings {
+            round_ids: None,
+            limit: None,
+            player: None,
+            recipient: None,
+        })
+        .unwrap(),
+        funds: vec![],
+    });
+    router
+        .execute_multi(Addr::unchecked("user3"), [claim_msg].to_vec())
+        .unwrap();
+
+    let round_bets: RoundBetsResponse = router
+        .wrap()
+        .query_wasm_smart(
+            prediction_market_addr,
+            &QueryMsg::RoundBets {
+                round_id: Uint128::zero(),
+                direction: None,
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+    let players: Vec<Addr> =
+        round_bets.bets.into_iter().map(|bet| bet.player).collect();
+    assert_eq!(
+        players,
+        vec![Addr::unchecked("user1"), Addr::unchecked("user3")]
+    );
+}
+
+#[test]
+fn test_player_stats() {
+    let mut router = mock_app();
+    let owner = Addr::unchecked("owner");
+
+    let prediction_market_addr =
+        create_prediction_market(&mut router, &owner, default_config());
+
+    start_next_round(&mut router, &prediction_market_addr, &owner);
+
+    let config: ConfigResponse = router
+        .wrap()
+        .query_wasm_smart(
+            prediction_market_addr.to_string(),
+            &QueryMsg::Config {},
+        )
+        .unwrap();
+
+    for (user, direction) in
+        [("user1", Direction::Bull), ("user2", Direction::Bear)]
+    {
+        execute_bet(
+            &mut router,
+            Addr::unchecked(user),
+            Uint128::new(100),
+            direction,
+            &config.token_addr,
+            &prediction_market_addr,
+            Uint128::zero(),
+        );
+    }
+
+    start_next_round(&mut router, &prediction_market_addr, &owner);
+    update_price(&mut router, config.clone(), Uint128::new(1_050_000), &owner);
+    start_next_round(&mut router, &prediction_market_addr, &owner);
+
+    /* user2 never claims, the keeper records the loss anyway */
+    settle_round(&mut router, &prediction_market_addr, Uint128::zero());
+    let claim_msg: CosmosMsg = CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: prediction_market_addr.to_string(),
+        msg: to_binary(&ExecuteMsg::CollectWinnings {
+            round_ids: None,
+            limit: None,
+            player: None,
+            recipient: None,
+        })
+        .unwrap(),
+        funds: vec![],
+    });
+    router
+        .execute_multi(Addr::unchecked("user1"), [claim_msg].to_vec())
+        .unwrap();
+    assert_eq!(
+        query_cw20_balance(&router, &config.token_addr, "user1"),
+        1094
+    );
+
+    let player_stats: PlayerStats = router
+        .wrap()
+        .query_wasm_smart(
+            prediction_market_addr.clone(),
+            &QueryMsg::PlayerStats {
+                player: "user1".to_string(),
+            },
+        )
+        .unwrap();
+    assert_eq!(
+        player_stats,
+        PlayerStats {
+            rounds_played: 1,
+            wins: 1,
+            losses: 0,
+            ties: 0,
+            total_staked: Uint128::new(97),
+            total_won: Uint128::new(194),
+            net_pnl: 97,
+            current_streak: 1,
+            best_streak: 1,
+        }
+    );
+
+    let leaderboard: LeaderboardResponse = router
+        .wrap()
+        .query_wasm_smart(
+            prediction_market_addr.clone(),
+            &QueryMsg::Leaderboard {
+                order_by: LeaderboardOrder::Pnl,
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+    let players: Vec<Addr> = leaderboard
+        .entries
+        .iter()
+        .map(|entry| entry.player.clone())
+        .collect();
+    assert_eq!(
+        players,
+        vec![Addr::unchecked("user1"), Addr::unchecked("user2")]
+    );
+    assert_eq!(leaderboard.entries[1].stats.net_pnl, -97);
+
+    let leaderboard: LeaderboardResponse = router
+        .wrap()
+        .query_wasm_smart(
+            prediction_market_addr,
+            &QueryMsg::Leaderboard {
+                order_by: LeaderboardOrder::Pnl,
+                start_after: Some("user1".to_string()),
+                limit: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(leaderboard.entries.len(), 1);
+    assert_eq!(leaderboard.entries[0].player, Addr::unchecked("user2"));
+}
+
+#[test]
+fn test_global_stats() {
+    let mut router = mock_app();
+    let owner = Addr::unchecked("owner");
+
+    let prediction_market_addr =
+        create_prediction_market(&mut router, &owner, default_config());
+
+    start_next_round(&mut router, &prediction_market_addr, &owner);
+
+    let config: ConfigResponse = router
+        .wrap()
+        .query_wasm_smart(
+            prediction_market_addr.to_string(),
+            &QueryMsg::Config {},
+        )
+        .unwrap();
+
+    for (user, direction) in [
+        ("user1", Direction::Bull),
+        ("user1", Direction::Bull),
+        ("user2", Direction::Bear),
+    ] {
+        execute_bet(
+            &mut router,
+            Addr::unchecked(user),
+            Uint128::new(100),
+            direction,
+            &config.token_addr,
+            &prediction_market_addr,
+            Uint128::zero(),
+        );
+    }
+
+    /* A withdrawn bet leaves only its burn and fee behind */
+    execute_bet(
+        &mut router,
+        Addr::unchecked("user3"),
+        Uint128::new(100),
+        Direction::Bull,
+        &config.token_addr,
+        &prediction_market_addr,
+        Uint128::zero(),
+    );
+    let withdraw_msg: CosmosMsg = CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: prediction_market_addr.to_string(),
+        msg: to_binary(&ExecuteMsg::WithdrawBet {
+            round_id: Uint128::zero(),
+            amount: None,
+        })
+        .unwrap(),
+        funds: vec![],
+    });
+    router
+        .execute_multi(Addr::unchecked("user3"), [withdraw_msg].to_vec())
+        .unwrap();
+
+    start_next_round(&mut router, &prediction_market_addr, &owner);
+    update_price(&mut router, config, Uint128::new(1_050_000), &owner);
+    start_next_round(&mut router, &prediction_market_addr, &owner);
+
+    let claim_msg: CosmosMsg = CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: prediction_market_addr.to_string(),
+        msg: to_binary(&ExecuteMsg::CollectWinnings {
+            round_ids: None,
+            limit: None,
+            player: None,
+            recipient: None,
+        })
+        .unwrap(),
+        funds: vec![],
+    });
+    router
+        .execute_multi(Addr::unchecked("user1"), [claim_msg].to_vec())
+        .unwrap();
+
+    let global_stats: GlobalStats = router
+        .wrap()
+        .query_wasm_smart(prediction_market_addr, &QueryMsg::GlobalStats {})
+        .unwrap();
+    assert_eq!(
+        global_stats,
+        GlobalStats {
+            bull_volume: Uint128::new(194),
+            bear_volume: Uint128::new(97),
+            bet_count: 2,
+            unique_players: 3,
+            total_burned: Uint128::new(4),
+            total_fees_accrued: Uint128::new(8),
+            total_fees_distributed: Uint128::zero(),
+            total_paid_out: Uint128::new(291),
+        }
+    );
+}
+
+#[test]
+fn test_referral() {
+    let mut router = mock_app();
+    let owner = Addr::unchecked("owner");
+
+    /* Half of the 2% gaming fee goes to the referrer */
+    let default_config = Config {
+        referral_share: Uint128::new(5000u128),
+        ..default_config()
+    };
+
+    let prediction_market_addr =
+        create_prediction_market(&mut router, &owner, default_config);
+
+    start_next_round(&mut router, &prediction_market_addr, &owner);
+
+    let config: ConfigResponse = router
+        .wrap()
+        .query_wasm_smart(
+            prediction_market_addr.to_string(),
+            &QueryMsg::Config {},
+        )
+        .unwrap();
+
+    let increase_allowance_msg: CosmosMsg = CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: config.token_addr.to_string(),
+        msg: to_binary(&Cw20ExecuteMsg::IncreaseAllowance {
+            spender: prediction_market_addr.to_string(),
+            amount: Uint128::new(100),
+            expires: None,
+        })
+        .unwrap(),
+        funds: vec![],
+    });
+    let bet_msg = |user: &str| -> CosmosMsg {
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: prediction_market_addr.to_string(),
+            msg: to_binary(&ExecuteMsg::BetBull {
+                round_id: Uint128::zero(),
+                amount: Uint128::new(100),
+                token: None,
+                referrer: Some(user.to_string()),
+            })
+            .unwrap(),
+            funds: vec![],
+        })
+    };
+
+    /* Nobody can refer themselves */
+    router
+        .execute_multi(
+            Addr::unchecked("user1"),
+            [increase_allowance_msg.clone(), bet_msg("user1")].to_vec(),
+        )
+        .unwrap_err();
+    router
+        .execute_multi(
+            Addr::unchecked("user1"),
+            [increase_allowance_msg, bet_msg("user3")].to_vec(),
+        )
+        .unwrap();
+
+    /* Later bets credit the bound referrer */
+    execute_bet(
+        &mut router,
+        Addr::unchecked("user1"),
+        Uint128::new(100),
+        Direction::Bull,
+        &config.token_addr,
+        &prediction_market_addr,
+        Uint128::zero(),
+    );
+
+    /* Bets sent through the token carry their referrer too */
+    send_bet(
+        &mut router,
+        "user2",
+        Uint128::new(100),
+        ReceiveMsg::BetBull {
+            round_id: Uint128::zero(),
+            referrer: Some("user3".to_string()),
+        },
+        &config.token_addr,
+        &prediction_market_addr,
+    );
+
+    let referral_earnings: ReferralEarningsResponse = router
+        .wrap()
+        .query_wasm_smart(
+            prediction_market_addr.clone(),
+            &QueryMsg::ReferralEarnings {
+                referrer: "user3".to_string(),
+            },
+        )
+        .unwrap();
+    assert_eq!(
+        referral_earnings.earnings,
+        vec![AssetAmount {
+            info: AssetInfo::Token {
+                contract_addr: config.token_addr.clone(),
+            },
+            amount: Uint128::new(3),
+        }]
+    );
+    assert_eq!(referral_earnings.claimable, referral_earnings.earnings);
+
+    let referred_players: ReferredPlayersResponse = router
+        .wrap()
+        .query_wasm_smart(
+            prediction_market_addr.clone(),
+            &QueryMsg::ReferredPlayers {
+                referrer: "user3".to_string(),
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(
+        referred_players.players,
+        vec![Addr::unchecked("user1"), Addr::unchecked("user2")]
+    );
+
+    let claim_msg: CosmosMsg = CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: prediction_market_addr.to_string(),
+        msg: to_binary(&ExecuteMsg::ClaimReferralRewards {}).unwrap(),
+        funds: vec![],
+    });
+    router
+        .execute_multi(Addr::unchecked("user3"), [claim_msg.clone()].to_vec())
+        .unwrap();
+    /* Rewards are paid once */
+    router
+        .execute_multi(Addr::unchecked("user3"), [claim_msg].to_vec())
+        .unwrap_err();
+
+    let user3_balance: BalanceResponse = router
+        .wrap()
+        .query_wasm_smart(
+            config.token_addr.to_string(),
+            &Cw20QueryMsg::Balance {
+                address: "user3".to_string(),
+            },
+        )
+        .unwrap();
+    assert_eq!(user3_balance.balance, Uint128::new(1003));
+}
+
+#[test]
+fn test_fee_tiers() {
+    let mut router = mock_app();
+    let owner = Addr::unchecked("owner");
+
+    let prediction_market_addr =
+        create_prediction_market(&mut router, &owner, default_config());
+
+    start_next_round(&mut router, &prediction_market_addr, &owner);
+
+    let config: ConfigResponse = router
+        .wrap()
+        .query_wasm_smart(
+            prediction_market_addr.to_string(),
+            &QueryMsg::Config {},
+        )
+        .unwrap();
+
+    /* Half off after 100 of volume, no fees for holders of 1000 tokens */
+    let tiers_msg: CosmosMsg = CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: prediction_market_addr.to_string(),
+        msg: to_binary(&ExecuteMsg::SetFeeTiers {
+            tiers: vec![
+                FeeTier {
+                    name: "regular".to_string(),
+                    requirement: FeeTierRequirement::Volume {
+                        amount: Uint128::new(100),
+                    },
+                    discount: Uint128::new(5000u128),
+                },
+                FeeTier {
+                    name: "holder".to_string(),
+                    requirement: FeeTierRequirement::Cw20Balance {
+                        contract_addr: config.token_addr.clone(),
+                        amount: Uint128::new(1000),
+                    },
+                    discount: Uint128::new(10000u128),
+                },
+            ],
+        })
+        .unwrap(),
+        funds: vec![],
+    });
+    router
+        .execute_multi(Addr::unchecked("user1"), [tiers_msg.clone()].to_vec())
+        .unwrap_err();
+    router
+        .execute_multi(owner.clone(), [tiers_msg].to_vec())
+        .unwrap();
+
+    for _ in 0..2 {
+        execute_bet(
+            &mut router,
+            Addr::unchecked("user1"),
+            Uint128::new(100),
+            Direction::Bull,
+            &config.token_addr,
+            &prediction_market_addr,
+            Uint128::zero(),
+        );
+    }
+    execute_bet(
+        &mut router,
+        Addr::unchecked("user2"),
+        Uint128::new(100),
+        Direction::Bear,
+        &config.token_addr,
+        &prediction_market_addr,
+        Uint128::zero(),
+    );
+
+    let fee_tier: PlayerFeeTierResponse = router
+        .wrap()
+        .query_wasm_smart(
+            prediction_market_addr.clone(),
+            &QueryMsg::PlayerFeeTier {
+                player: "user1".to_string(),
+            },
+        )
+        .unwrap();
+    assert_eq!(fee_tier.tier.unwrap().name, "regular");
+    assert_eq!(fee_tier.trailing_volume, Uint128::new(199));
+
+    /*
+     * user1's first bet was free while they held 1000 tokens, the second
+     * paid no burn fee and half the gaming fee
+     */
+    let status: StatusResponse = router
+        .wrap()
+        .query_wasm_smart(prediction_market_addr, &QueryMsg::Status {})
+        .unwrap();
+    let bidding_round = status.bidding_round.unwrap();
+    assert_eq!(bidding_round.bull_amount, Uint128::new(199));
+    assert_eq!(bidding_round.bear_amount, Uint128::new(100));
+}
+
+#[test]
+fn test_fee_distribution() {
+    let mut router = mock_app();
+    let owner = Addr::unchecked("owner");
+
+    let prediction_market_addr =
+        create_prediction_market(&mut router, &owner, default_config());
+
+    start_next_round(&mut router, &prediction_market_addr, &owner);
+
+    let config: ConfigResponse = router
+        .wrap()
+        .query_wasm_smart(
+            prediction_market_addr.to_string(),
+            &QueryMsg::Config {},
+        )
+        .unwrap();
+
+    let distribute_msg: CosmosMsg = CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: prediction_market_addr.to_string(),
+        msg: to_binary(&ExecuteMsg::DistributeFund {}).unwrap(),
+        funds: vec![],
+    });
+
+    /* Nobody to pay yet */
+    router
+        .execute_multi(owner.clone(), [distribute_msg.clone()].to_vec())
+        .unwrap_err();
+
+    let recipients_msg = |ratio: u128| -> CosmosMsg {
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: prediction_market_addr.to_string(),
+            msg: to_binary(&ExecuteMsg::SetFeeRecipients {
+                recipients: vec![
+                    WalletInfo {
+                        address: Addr::unchecked("admin1"),
+                        ratio: Decimal::percent(50),
+                    },
+                    WalletInfo {
+                        address: Addr::unchecked("admin2"),
+                        ratio: Decimal::percent(ratio as u64),
+                    },
+                ],
+            })
+            .unwrap(),
+            funds: vec![],
+        })
+    };
+    router
+        .execute_multi(owner.clone(), [recipients_msg(60)].to_vec())
+        .unwrap_err();
+    router
+        .execute_multi(owner.clone(), [recipients_msg(50)].to_vec())
+        .unwrap();
+
+    execute_bet(
+        &mut router,
+        Addr::unchecked("user1"),
+        Uint128::new(150),
+        Direction::Bull,
+        &config.token_addr,
+        &prediction_market_addr,
+        Uint128::zero(),
+    );
+
+    /* Fees of rounds still in play can't be paid out */
+    router
+        .execute_multi(owner.clone(), [distribute_msg.clone()].to_vec())
+        .unwrap_err();
+
+    /* A gaming fee of 3 splits into 1 each, 1 is carried over */
+    start_next_round(&mut router, &prediction_market_addr, &owner);
+    start_next_round(&mut router, &prediction_market_addr, &owner);
+    router
+        .execute_multi(owner.clone(), [distribute_msg.clone()].to_vec())
+        .unwrap();
+
+    /* The dust alone doesn't split */
+    router
+        .execute_multi(owner.clone(), [distribute_msg.clone()].to_vec())
+        .unwrap_err();
+
+    execute_bet(
+        &mut router,
+        Addr::unchecked("user2"),
+        Uint128::new(150),
+        Direction::Bear,
+        &config.token_addr,
+        &prediction_market_addr,
+        Uint128::new(2),
+    );
+    start_next_round(&mut router, &prediction_market_addr, &owner);
+    start_next_round(&mut router, &prediction_market_addr, &owner);
+    router
+        .execute_multi(owner, [distribute_msg].to_vec())
+        .unwrap();
+
+    let admin1_balance: BalanceResponse = router
+        .wrap()
+        .query_wasm_smart(
+            config.token_addr.to_string(),
+            &Cw20QueryMsg::Balance {
+                address: "admin1".to_string(),
+            },
+        )
+        .unwrap();
+    assert_eq!(admin1_balance.balance, Uint128::new(3));
+
+    let distributions: FeeDistributionsResponse = router
+        .wrap()
+        .query_wasm_smart(
+            prediction_market_addr,
+            &QueryMsg::FeeDistributions {
+                start_after: Some(0),
+                limit: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(distributions.distributions.len(), 1);
+    assert_eq!(
+        distributions.distributions[0].amounts,
+        vec![AssetAmount {
+            info: AssetInfo::Token {
+                contract_addr: config.token_addr,
+            },
+            amount: Uint128::new(4),
+        }]
+    );
+}
+
+#[test]
+fn test_internal_fee_staking() {
+    let mut router = mock_app();
+    let owner = Addr::unchecked("owner");
+
+    router.set_block(BlockInfo {
+        height: 0,
+        time: Timestamp::from_seconds(0),
+        chain_id: "testing".to_string(),
+    });
+
+    /* Stakers of the market's own token get half of the fees */
+    let fast_oracle_addr = init_fast_oracle_contract(&mut router, &owner);
+    let token_addr = init_cw20_Contract(&mut router, &owner);
+    let msg = InstantiateMsg {
+        config: Config {
+            fast_oracle_addr,
+            token_addr: token_addr.clone(),
+            fee_staking: FeeStaking::Internal {
+                token_addr: token_addr.clone(),
+            },
+            staking_share: Uint128::new(5000u128),
+            staking_lock_seconds: Uint128::new(3600u128),
+            ..default_config()
+        },
+    };
+    let prediction_market_code_id =
+        router.store_code(contract_price_prediction());
+    let prediction_market_addr = router
+        .instantiate_contract(
+            prediction_market_code_id,
+            owner.clone(),
+            &msg,
+            &[],
+            "prediction_market",
+            Some(owner.to_string()),
+        )
+        .unwrap();
+
+    send_bet(
+        &mut router,
+        "user3",
+        Uint128::new(100),
+        ReceiveMsg::Stake {},
+        &token_addr,
+        &prediction_market_addr,
+    );
+
+    start_next_round(&mut router, &prediction_market_addr, &owner);
+    execute_bet(
+        &mut router,
+        Addr::unchecked("user1"),
+        Uint128::new(150),
+        Direction::Bull,
+        &token_addr,
+        &prediction_market_addr,
+        Uint128::zero(),
+    );
+    start_next_round(&mut router, &prediction_market_addr, &owner);
+    start_next_round(&mut router, &prediction_market_addr, &owner);
+
+    let recipients_msg: CosmosMsg = CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: prediction_market_addr.to_string(),
+        msg: to_binary(&ExecuteMsg::SetFeeRecipients {
+            recipients: vec![WalletInfo {
+                address: Addr::unchecked("admin1"),
+                ratio: Decimal::one(),
+            }],
+        })
+        .unwrap(),
+        funds: vec![],
+    });
+    let distribute_msg: CosmosMsg = CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: prediction_market_addr.to_string(),
+        msg: to_binary(&ExecuteMsg::DistributeFund {}).unwrap(),
+        funds: vec![],
+    });
+    router
+        .execute_multi(owner, [recipients_msg, distribute_msg].to_vec())
+        .unwrap();
+
+    /* A gaming fee of 3: 1 for the stakers, 2 for the recipients */
+    let staker: StakerResponse = router
+        .wrap()
+        .query_wasm_smart(
+            prediction_market_addr.clone(),
+            &QueryMsg::Staker {
+                address: "user3".to_string(),
+            },
+        )
+        .unwrap();
+    assert_eq!(staker.amount, Uint128::new(100));
+    assert_eq!(staker.pending_reward, Uint128::new(1));
+    assert_eq!(staker.locked_until, Timestamp::from_seconds(3600));
+
+    let claim_msg: CosmosMsg = CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: prediction_market_addr.to_string(),
+        msg: to_binary(&ExecuteMsg::ClaimStakingRewards {}).unwrap(),
+        funds: vec![],
+    });
+    let unstake_msg: CosmosMsg = CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: prediction_market_addr.to_string(),
+        msg: to_binary(&ExecuteMsg::Unstake {
+            amount: Uint128::new(100),
+        })
+        .unwrap(),
+        funds: vec![],
+    });
+    router
+        .execute_multi(Addr::unchecked("user3"), [claim_msg].to_vec())
+        .unwrap();
+
+    /* Staked at 0, it's 1800 seconds later */
+    router
+        .execute_multi(Addr::unchecked("user3"), [unstake_msg.clone()].to_vec())
+        .unwrap_err();
+    router.update_block(|block| {
+        block.time = block.time.plus_seconds(1800);
+        block.height += 1;
+    });
+    router
+        .execute_multi(Addr::unchecked("user3"), [unstake_msg].to_vec())
+        .unwrap();
+
+    for (address, balance) in [("user3", 1001u128), ("admin1", 2u128)] {
+        let balance_response: BalanceResponse = router
+            .wrap()
+            .query_wasm_smart(
+                token_addr.to_string(),
+                &Cw20QueryMsg::Balance {
+                    address: address.to_string(),
+                },
+            )
+            .unwrap();
+        assert_eq!(balance_response.balance, Uint128::new(balance));
+    }
+}
+
+#[test]
+fn test_fee_staking_dust() {
+    let mut router = mock_app();
+    let owner = Addr::unchecked("owner");
+
+    let fast_oracle_addr = init_fast_oracle_contract(&mut router, &owner);
+    let token_addr = init_cw20_Contract(&mut router, &owner);
+    let msg = InstantiateMsg {
+        config: Config {
+            fast_oracle_addr,
+            token_addr: token_addr.clone(),
+            fee_staking: FeeStaking::Internal {
+                token_addr: token_addr.clone(),
+            },
+            staking_share: Uint128::new(5000u128),
+            ..default_config()
+        },
+    };
+    let prediction_market_code_id =
+        router.store_code(contract_price_prediction());
+    let prediction_market_addr = router
+        .instantiate_contract(
+            prediction_market_code_id,
+            owner.clone(),
+            &msg,
+            &[],
+            "prediction_market",
+            Some(owner.to_string()),
+        )
+        .unwrap();
+
+    send_bet(
+        &mut router,
+        "user3",
+        Uint128::new(3),
+        ReceiveMsg::Stake {},
+        &token_addr,
+        &prediction_market_addr,
+    );
+
+    let recipients_msg: CosmosMsg = CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: prediction_market_addr.to_string(),
+        msg: to_binary(&ExecuteMsg::SetFeeRecipients {
+            recipients: vec![WalletInfo {
+                address: Addr::unchecked("admin1"),
+                ratio: Decimal::one(),
+            }],
+        })
+        .unwrap(),
+        funds: vec![],
+    });
+    router
+        .execute_multi(owner.clone(), [recipients_msg].to_vec())
+        .unwrap();
+
+    /* Two distributions of 1 for the stakers of 3 */
+    start_next_round(&mut router, &prediction_market_addr, &owner);
+    for round_id in [0u128, 2u128] {
+        execute_bet(
+            &mut router,
+            Addr::unchecked("user1"),
+            Uint128::new(100),
+            Direction::Bull,
+            &token_addr,
+            &prediction_market_addr,
+            Uint128::new(round_id),
+        );
+        start_next_round(&mut router, &prediction_market_addr, &owner);
+        start_next_round(&mut router, &prediction_market_addr, &owner);
+
+        let distribute_msg: CosmosMsg = CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: prediction_market_addr.to_string(),
+            msg: to_binary(&ExecuteMsg::DistributeFund {}).unwrap(),
+            funds: vec![],
+        });
+        router
+            .execute_multi(owner.clone(), [distribute_msg].to_vec())
+            .unwrap();
+    }
+
+    /* The 1 lost to rounding the first time is shared out the second */
+    let staker: StakerResponse = router
+        .wrap()
+        .query_wasm_smart(
+            prediction_market_addr.clone(),
+            &QueryMsg::Staker {
+                address: "user3".to_string(),
+            },
+        )
+        .unwrap();
+    assert_eq!(staker.pending_reward, Uint128::new(2));
+
+    let state: StakingState = router
+        .wrap()
+        .query_wasm_smart(prediction_market_addr, &QueryMsg::StakingState {})
+        .unwrap();
+    assert_eq!(state.undistributed, Uint128::new(1));
+}
+
+#[test]
+fn test_house_pool() {
+    let mut router = mock_app();
+    let owner = Addr::unchecked("owner");
+
+    let prediction_market_addr = create_prediction_market(
+        &mut router,
+        &owner,
+        Config {
+            house_seed_cap: Uint128::new(50u128),
+            house_fee_share: Uint128::new(5000u128),
+            ..default_config()
+        },
+    );
+    let config: ConfigResponse = router
+        .wrap()
+        .query_wasm_smart(
+            prediction_market_addr.to_string(),
+            &QueryMsg::Config {},
+        )
+        .unwrap();
+
+    send_bet(
+        &mut router,
+        "user4",
+        Uint128::new(200),
+        ReceiveMsg::DepositHouse {},
+        &config.token_addr,
+        &prediction_market_addr,
+    );
+
+    /* Both sides of round 0 get 50, user1 bets alone */
+    start_next_round(&mut router, &prediction_market_addr, &owner);
+    execute_bet(
+        &mut router,
+        Addr::unchecked("user1"),
+        Uint128::new(100),
+        Direction::Bull,
+        &config.token_addr,
+        &prediction_market_addr,
+        Uint128::zero(),
+    );
+
+    /* Round 1 takes the other 100 */
+    start_next_round(&mut router, &prediction_market_addr, &owner);
+    update_price(&mut router, config.clone(), Uint128::new(1_050_000), &owner);
+    /* The bull seed gets 197 * 50 / 147, round 2 is seeded with 33 */
+    start_next_round(&mut router, &prediction_market_addr, &owner);
+
+    let house: HousePoolResponse = router
+        .wrap()
+        .query_wasm_smart(
+            prediction_market_addr.clone(),
+            &QueryMsg::HousePool {},
+        )
+        .unwrap();
+    assert_eq!(
+        house.pool,
+        HousePool {
+            liquidity: Uint128::new(1),
+            seeded: Uint128::new(166),
+            total_shares: Uint128::new(200),
+            epoch: 1,
+            ..HousePool::default()
+        }
+    );
+    assert_eq!(house.nav, Uint128::new(167));
+
+    let claim_msg: CosmosMsg = CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: prediction_market_addr.to_string(),
+        msg: to_binary(&ExecuteMsg::CollectWinnings {
+            round_ids: None,
+            limit: None,
+            player: None,
+            recipient: None,
+        })
+        .unwrap(),
+        funds: vec![],
+    });
+    router
+        .execute_multi(Addr::unchecked("user1"), [claim_msg].to_vec())
+        .unwrap();
+
+    /* Half of the gaming fee of 2 goes to the house */
+    let recipients_msg: CosmosMsg = CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: prediction_market_addr.to_string(),
+        msg: to_binary(&ExecuteMsg::SetFeeRecipients {
+            recipients: vec![WalletInfo {
+                address: Addr::unchecked("admin1"),
+                ratio: Decimal::one(),
+            }],
+        })
+        .unwrap(),
+        funds: vec![],
+    });
+    let distribute_msg: CosmosMsg = CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: prediction_market_addr.to_string(),
+        msg: to_binary(&ExecuteMsg::DistributeFund {}).unwrap(),
+        funds: vec![],
+    });
+    router
+        .execute_multi(owner, [recipients_msg, distribute_msg].to_vec())
+        .unwrap();
+
+    let shares: HouseSharesResponse = router
+        .wrap()
+        .query_wasm_smart(
+            prediction_market_addr.clone(),
+            &QueryMsg::HouseShares {
+                address: "user4".to_string(),
+            },
+        )
+        .unwrap();
+    assert_eq!(shares.shares, Uint128::new(200));
+    assert_eq!(shares.value, Uint128::new(168));
+
+    let withdraw_msg = |shares: u128| -> CosmosMsg {
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: prediction_market_addr.to_string(),
+            msg: to_binary(&ExecuteMsg::WithdrawHouse {
+                shares: Uint128::new(shares),
+            })
+            .unwrap(),
+            funds: vec![],
+        })
+    };
+    let claim_house_msg: CosmosMsg = CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: prediction_market_addr.to_string(),
+        msg: to_binary(&ExecuteMsg::ClaimHouse {}).unwrap(),
+        funds: vec![],
+    });
+    router
+        .execute_multi(Addr::unchecked("user4"), [withdraw_msg(201)].to_vec())
+        .unwrap_err();
+    router
+        .execute_multi(Addr::unchecked("user4"), [withdraw_msg(200)].to_vec())
+        .unwrap();
+    /* Queued until the live round closes */
+    router
+        .execute_multi(
+            Addr::unchecked("user4"),
+            [claim_house_msg.clone()].to_vec(),
+        )
+        .unwrap_err();
+    let shares: HouseSharesResponse = router
+        .wrap()
+        .query_wasm_smart(
+            prediction_market_addr.clone(),
+            &QueryMsg::HouseShares {
+                address: "user4".to_string(),
+            },
+        )
+        .unwrap();
+    assert_eq!(shares.shares, Uint128::zero());
+    assert_eq!(shares.pending_shares, Uint128::new(200));
+
+    /*
+     * Round 1 ties and gives back its 100, but the 168 also need the 66
+     * seeded in round 2, which isn't reseeded
+     */
+    start_next_round(&mut router, &prediction_market_addr, &owner);
+    router
+        .execute_multi(
+            Addr::unchecked("user4"),
+            [claim_house_msg.clone()].to_vec(),
+        )
+        .unwrap_err();
+    start_next_round(&mut router, &prediction_market_addr, &owner);
+
+    let house: HousePoolResponse = router
+        .wrap()
+        .query_wasm_smart(
+            prediction_market_addr.clone(),
+            &QueryMsg::HousePool {},
+        )
+        .unwrap();
+    assert_eq!(
+        house.pool,
+        HousePool {
+            unclaimed: Uint128::new(168),
+            epoch: 2,
+            ..HousePool::default()
+        }
+    );
+    let shares: HouseSharesResponse = router
+        .wrap()
+        .query_wasm_smart(
+            prediction_market_addr.clone(),
+            &QueryMsg::HouseShares {
+                address: "user4".to_string(),
+            },
+        )
+        .unwrap();
+    assert_eq!(shares.claimable, Uint128::new(168));
+    router
+        .execute_multi(Addr::unchecked("user4"), [claim_house_msg].to_vec())
+        .unwrap();
+
+    for (address, balance) in
+        [("user1", 1029u128), ("user4", 968u128), ("admin1", 1u128)]
+    {
+        let balance_response: BalanceResponse = router
+            .wrap()
+            .query_wasm_smart(
+                config.token_addr.to_string(),
+                &Cw20QueryMsg::Balance {
+                    address: address.to_string(),
+                },
+            )
+            .unwrap();
+        assert_eq!(balance_response.balance, Uint128::new(balance));
+    }
+}
+
+#[test]
+fn test_house_seed_tie() {
+    let mut router = mock_app();
+    let owner = Addr::unchecked("owner");
+
+    let prediction_market_addr = create_prediction_market(
+        &mut router,
+        &owner,
+        Config {
+            tie_policy: TiePolicy::HouseTakes,
+            house_seed_cap: Uint128::new(50u128),
+            ..default_config()
+        },
+    );
+    let config: ConfigResponse = router
+        .wrap()
+        .query_wasm_smart(
+            prediction_market_addr.to_string(),
+            &QueryMsg::Config {},
+        )
+        .unwrap();
+
+    send_bet(
+        &mut router,
+        "user4",
+        Uint128::new(200),
+        ReceiveMsg::DepositHouse {},
+        &config.token_addr,
+        &prediction_market_addr,
+    );
+
+    start_next_round(&mut router, &prediction_market_addr, &owner);
+    for (user, direction) in
+        [("user1", Direction::Bull), ("user2", Direction::Bear)]
+    {
+        execute_bet(
+            &mut router,
+            Addr::unchecked(user),
+            Uint128::new(100),
+            direction,
+            &config.token_addr,
+            &prediction_market_addr,
+            Uint128::zero(),
+        );
+    }
+    start_next_round(&mut router, &prediction_market_addr, &owner);
+    start_next_round(&mut router, &prediction_market_addr, &owner);
+
+    /* The house takes the players' 194 and their fees, not its own seeds */
+    let global_stats: GlobalStats = router
+        .wrap()
+        .query_wasm_smart(
+            prediction_market_addr.clone(),
+            &QueryMsg::GlobalStats {},
+        )
+        .unwrap();
+    assert_eq!(global_stats.total_fees_accrued, Uint128::new(198));
+
+    let house: HousePoolResponse = router
+        .wrap()
+        .query_wasm_smart(prediction_market_addr, &QueryMsg::HousePool {})
+        .unwrap();
+    assert_eq!(house.nav, Uint128::new(200));
+}
+
+#[test]
+fn test_fee_on_winnings_house_seed() {
+    let mut router = mock_app();
+    let owner = Addr::unchecked("owner");
+
+    let prediction_market_addr = create_prediction_market(
+        &mut router,
+        &owner,
+        Config {
+            fee_model: FeeModel::OnWinnings,
+            house_seed_cap: Uint128::new(50u128),
+            ..default_config()
+        },
+    );
+    let config: ConfigResponse = router
+        .wrap()
+        .query_wasm_smart(
+            prediction_market_addr.to_string(),
+            &QueryMsg::Config {},
+        )
+        .unwrap();
+
+    send_bet(
+        &mut router,
+        "user4",
+        Uint128::new(200),
+        ReceiveMsg::DepositHouse {},
+        &config.token_addr,
+        &prediction_market_addr,
+    );
+
+    start_next_round(&mut router, &prediction_market_addr, &owner);
+    execute_bet(
+        &mut router,
+        Addr::unchecked("user1"),
+        Uint128::new(100),
+        Direction::Bull,
+        &config.token_addr,
+        &prediction_market_addr,
+        Uint128::zero(),
+    );
+
+    /* 2% of user1's 100, the seeds of 100 pay nothing */
+    let odds: OddsResponse = router
+        .wrap()
+        .query_wasm_smart(
+            prediction_market_addr.clone(),
+            &QueryMsg::Odds {
+                round_id: Uint128::zero(),
+            },
+        )
+        .unwrap();
+    assert_eq!(
+        odds.bull_multiplier,
+        Some(Decimal::from_ratio(198u128, 150u128))
+    );
+
+    start_next_round(&mut router, &prediction_market_addr, &owner);
+    update_price(&mut router, config.clone(), Uint128::new(1_050_000), &owner);
+    start_next_round(&mut router, &prediction_market_addr, &owner);
+    /* Round 1 is won with only the house in it */
+    update_price(&mut router, config.clone(), Uint128::new(1_100_000), &owner);
+    start_next_round(&mut router, &prediction_market_addr, &owner);
+
+    for (round_id, fee_amount) in [(0u128, 2u128), (1u128, 0u128)] {
+        let round: FinishedRound = router
+            .wrap()
+            .query_wasm_smart(
+                prediction_market_addr.clone(),
+                &QueryMsg::FinishedRound {
+                    round_id: Uint128::new(round_id),
+                },
+            )
+            .unwrap();
+        assert_eq!(round.fee_amount, Uint128::new(fee_amount));
+    }
+
+    let global_stats: GlobalStats = router
+        .wrap()
+        .query_wasm_smart(
+            prediction_market_addr.clone(),
+            &QueryMsg::GlobalStats {},
+        )
+        .unwrap();
+    assert_eq!(global_stats.total_fees_accrued, Uint128::new(2));
+
+    /* The bull seed gets 198 * 50 / 150 of round 0 and all of round 1 */
+    let house: HousePoolResponse = router
+        .wrap()
+        .query_wasm_smart(
+            prediction_market_addr.clone(),
+            &QueryMsg::HousePool {},
+        )
+        .unwrap();
+    assert_eq!(house.nav, Uint128::new(166));
+
+    let claim_msg: CosmosMsg = CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: prediction_market_addr.to_string(),
+        msg: to_binary(&ExecuteMsg::CollectWinnings {
+            round_ids: None,
+            limit: None,
+            player: None,
+            recipient: None,
+        })
+        .unwrap(),
+        funds: vec![],
+    });
+    router
+        .execute_multi(Addr::unchecked("user1"), [claim_msg].to_vec())
+        .unwrap();
+    assert_eq!(
+        query_cw20_balance(&router, &config.token_addr, "user1"),
+        1032u128
+    );
+}
+
+#[test]
+fn test_fee_on_winnings() {
+    let mut router = mock_app();
+    let owner = Addr::unchecked("owner");
+
+    let prediction_market_addr = create_prediction_market(
+        &mut router,
+        &owner,
+        Config {
+            fee_model: FeeModel::OnWinnings,
+            ..default_config()
+        },
+    );
+    let config: ConfigResponse = router
+        .wrap()
+        .query_wasm_smart(
+            prediction_market_addr.to_string(),
+            &QueryMsg::Config {},
+        )
+        .unwrap();
+
+    start_next_round(&mut router, &prediction_market_addr, &owner);
+    for (user, direction) in
+        [("user1", Direction::Bull), ("user2", Direction::Bear)]
+    {
+        execute_bet(
+            &mut router,
+            Addr::unchecked(user),
+            Uint128::new(100),
+            direction,
+            &config.token_addr,
+            &prediction_market_addr,
+            Uint128::zero(),
+        );
+    }
+
+    /* 2% of the pool of 200 comes off the winnings */
+    let odds: OddsResponse = router
+        .wrap()
+        .query_wasm_smart(
+            prediction_market_addr.clone(),
+            &QueryMsg::Odds {
+                round_id: Uint128::zero(),
+            },
+        )
+        .unwrap();
+    assert_eq!(
+        odds.bull_multiplier,
+        Some(Decimal::from_ratio(196u128, 100u128))
+    );
+
+    /* Round 0 keeps the rate it opened with */
+    let update_msg: CosmosMsg = CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: prediction_market_addr.to_string(),
+        msg: to_binary(&ExecuteMsg::UpdateConfig {
+            config: PartialConfig {
+                next_round_seconds: None,
+                fast_oracle_addr: None,
+                minimum_bet: None,
+                burn_fee: None,
+                gaming_fee: Some(Uint128::new(500)),
+                token_addr: None,
+                tie_policy: None,
+                tie_band_bps: None,
+                withdrawal_penalty: None,
+                stake_denom: None,
+                push_settlement: None,
+                position_nft_addr: None,
+                bucket_bounds_bps: None,
+                referral_share: None,
+                fee_staking: None,
+                staking_share: None,
+                staking_lock_seconds: None,
+                house_seed_cap: None,
+                house_fee_share: None,
+                fee_model: None,
+                maximum_bet: None,
+                maximum_position: None,
+                maximum_round_pool: None,
+            },
+        })
+        .unwrap(),
+        funds: vec![],
+    });
+    router
+        .execute_multi(owner.clone(), [update_msg].to_vec())
+        .unwrap();
+    let odds: OddsResponse = router
+        .wrap()
+        .query_wasm_smart(
+            prediction_market_addr.clone(),
+            &QueryMsg::Odds {
+                round_id: Uint128::zero(),
+            },
+        )
+        .unwrap();
+    assert_eq!(
+        odds.bull_multiplier,
+        Some(Decimal::from_ratio(196u128, 100u128))
+    );
+
+    start_next_round(&mut router, &prediction_market_addr, &owner);
+    /* A one-sided round is refunded in full */
+    execute_bet(
+        &mut router,
+        Addr::unchecked("user3"),
+        Uint128::new(100),
+        Direction::Bull,
+        &config.token_addr,
+        &prediction_market_addr,
+        Uint128::new(1),
+    );
+    update_price(&mut router, config.clone(), Uint128::new(1_050_000), &owner);
+    start_next_round(&mut router, &prediction_market_addr, &owner);
+    start_next_round(&mut router, &prediction_market_addr, &owner);
+
+    let round: FinishedRound = router
+        .wrap()
+        .query_wasm_smart(
+            prediction_market_addr.clone(),
+            &QueryMsg::FinishedRound {
+                round_id: Uint128::zero(),
+            },
+        )
+        .unwrap();
+    assert_eq!(round.fee_model, FeeModel::OnWinnings);
+    assert_eq!(round.fee_amount, Uint128::new(4));
+
+    for user in ["user1", "user3"] {
+        let claim_msg: CosmosMsg = CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: prediction_market_addr.to_string(),
+            msg: to_binary(&ExecuteMsg::CollectWinnings {
+                round_ids: None,
+                limit: None,
+                player: None,
+                recipient: None,
+            })
+            .unwrap(),
+            funds: vec![],
+        });
+        router
+            .execute_multi(Addr::unchecked(user), [claim_msg].to_vec())
+            .unwrap();
+    }
+
+    for (address, balance) in
+        [("user1", 1096u128), ("user2", 900u128), ("user3", 1000u128)]
+    {
+        let balance_response: BalanceResponse = router
+            .wrap()
+            .query_wasm_smart(
+                config.token_addr.to_string(),
+                &Cw20QueryMsg::Balance {
+                    address: address.to_string(),
+                },
+            )
+            .unwrap();
+        assert_eq!(balance_response.balance, Uint128::new(balance));
+    }
+}
+
+#[test]
+fn test_bet_limits() {
+    let mut router = mock_app();
+    let owner = Addr::unchecked("owner");
+
+    let prediction_market_addr = create_prediction_market(
+        &mut router,
+        &owner,
+        Config {
+            minimum_bet: Uint128::new(10u128),
+            maximum_bet: Uint128::new(300u128),
+            maximum_position: Uint128::new(400u128),
+            maximum_round_pool: Uint128::new(500u128),
+            ..default_config()
+        },
+    );
+    let config: ConfigResponse = router
+        .wrap()
+        .query_wasm_smart(
+            prediction_market_addr.to_string(),
+            &QueryMsg::Config {},
+        )
+        .unwrap();
+
+    start_next_round(&mut router, &prediction_market_addr, &owner);
+
+    let bet = |router: &mut App, user: &str, amount: u128, bull: bool| {
+        let amount = Uint128::new(amount);
+        let increase_allowance_msg: CosmosMsg =
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: config.token_addr.to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::IncreaseAllowance {
+                    spender: prediction_market_addr.to_string(),
+                    amount,
+                    expires: None,
+                })
+                .unwrap(),
+                funds: vec![],
+            });
+        let bet_msg = if bull {
+            ExecuteMsg::BetBull {
+                amount,
+                round_id: Uint128::zero(),
+                token: None,
+                referrer: None,
+            }
+        } else {
+            ExecuteMsg::BetBear {
+                amount,
+                round_id: Uint128::zero(),
+                token: None,
+                referrer: None,
+            }
+        };
+        let bet_msg: CosmosMsg = CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: prediction_market_addr.to_string(),
+            msg: to_binary(&bet_msg).unwrap(),
+            funds: vec![],
+        });
+        router.execute_multi(
+            Addr::unchecked(user),
+            [increase_allowance_msg, bet_msg].to_vec(),
+        )
+    };
+
+    bet(&mut router, "user1", 5, true).unwrap_err();
+    bet(&mut router, "user1", 301, true).unwrap_err();
+    /* Positions of 291, 291 + 194 and 291 + 97 */
+    bet(&mut router, "user1", 300, true).unwrap();
+    bet(&mut router, "user1", 200, true).unwrap_err();
+    bet(&mut router, "user1", 100, true).unwrap();
+    /* Rounds of 388 + 194 and 388 + 97 */
+    bet(&mut router, "user2", 200, false).unwrap_err();
+    bet(&mut router, "user2", 100, false).unwrap();
+
+    let simulation: SimulateBetResponse = router
+        .wrap()
+        .query_wasm_smart(
+            prediction_market_addr.clone(),
+            &QueryMsg::SimulateBet {
+                round_id: Uint128::zero(),
+                direction: Direction::Bear,
+                gross: Uint128::new(50),
+                player: Some("user3".to_string()),
+            },
+        )
+        .unwrap();
+    assert!(simulation.error.is_some());
+}
+
+#[test]
+fn test_config_validation() {
+    let mut router = mock_app();
+    let owner = Addr::unchecked("owner");
+
+    let prediction_market_code_id =
+        router.store_code(contract_price_prediction());
+    let fast_oracle_addr = init_fast_oracle_contract(&mut router, &owner);
+    let token_addr = init_cw20_Contract(&mut router, &owner);
+    let valid_config = Config {
+        fast_oracle_addr,
+        token_addr,
+        ..default_config()
+    };
+
+    for config in [
+        Config {
+            next_round_seconds: Uint128::zero(),
+            ..valid_config.clone()
+        },
+        Config {
+            burn_fee: Uint128::new(5000u128),
+            gaming_fee: Uint128::new(5001u128),
+            ..valid_config.clone()
+        },
+        Config {
+            referral_share: Uint128::new(10001u128),
+            ..valid_config.clone()
+        },
+        Config {
+            minimum_bet: Uint128::new(100u128),
+            maximum_bet: Uint128::new(50u128),
+            ..valid_config.clone()
+        },
+        Config {
+            fast_oracle_addr: Addr::unchecked(""),
+            ..valid_config.clone()
+        },
+        Config {
+            stake_denom: Some("".to_string()),
+            ..valid_config.clone()
+        },
+        Config {
+            stake_denom: Some("1juno".to_string()),
+            ..valid_config.clone()
+        },
+    ] {
+        router
+            .instantiate_contract(
+                prediction_market_code_id,
+                owner.clone(),
+                &InstantiateMsg { config },
+                &[],
+                "prediction_market",
+                None,
+            )
+            .unwrap_err();
+    }
+
+    let prediction_market_addr = router
+        .instantiate_contract(
+            prediction_market_code_id,
+            owner.clone(),
+            &InstantiateMsg {
+                config: valid_config,
+            },
+            &[],
+            "prediction_market",
+            Some(owner.to_string()),
+        )
+        .unwrap();
+
+    let update_msg = |gaming_fee: u128| -> CosmosMsg {
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: prediction_market_addr.to_string(),
+            msg: to_binary(&ExecuteMsg::UpdateConfig {
+                config: PartialConfig {
+                    next_round_seconds: None,
+                    fast_oracle_addr: None,
+                    minimum_bet: None,
+                    burn_fee: None,
+                    gaming_fee: Some(Uint128::new(gaming_fee)),
+                    token_addr: None,
+                    tie_policy: None,
+                    tie_band_bps: None,
+                    withdrawal_penalty: None,
+                    stake_denom: None,
+                    push_settlement: None,
+                    position_nft_addr: None,
+                    bucket_bounds_bps: None,
+                    referral_share: None,
+                    fee_staking: None,
+                    staking_share: None,
+                    staking_lock_seconds: None,
+                    house_seed_cap: None,
+                    house_fee_share: None,
+                    fee_model: None,
+                    maximum_bet: None,
+                    maximum_position: None,
+                    maximum_round_pool: None,
+                },
+            })
+            .unwrap(),
+            funds: vec![],
+        })
+    };
+    /* With the burn fee of 1% */
+    router
+        .execute_multi(owner.clone(), [update_msg(9950)].to_vec())
+        .unwrap_err();
+    router
+        .execute_multi(owner, [update_msg(300)].to_vec())
+        .unwrap();
+
+    let config: ConfigResponse = router
+        .wrap()
+        .query_wasm_smart(prediction_market_addr, &QueryMsg::Config {})
+        .unwrap();
+    assert_eq!(config.gaming_fee, Uint128::new(300));
+}
@@ -1,2 +1,3 @@
 pub mod fast_oracle;
+pub mod position_nft;
 pub mod price_prediction;
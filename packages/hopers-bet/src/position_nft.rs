@@ -0,0 +1,77 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::price_prediction::{AssetInfo, Direction};
+use cosmwasm_std::{Binary, Uint128};
+
+/**
+ * What a position token stands for; it's the token's cw721 extension
+ */
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct PositionMetadata {
+    pub round_id: Uint128,
+    pub direction: Direction,
+    /* Net of fees, as it entered the pool */
+    pub amount: Uint128,
+    pub token: AssetInfo,
+}
+
+/**
+ * The subset of the cw721 interface the market uses; the market has to be
+ * the minter. Holders `SendNft` their positions to the market, which burns
+ * them once it owns them. Fee tiers query the holdings of other cw721
+ * contracts through `Tokens`
+ */
+pub mod msg {
+    use super::*;
+
+    #[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
+    #[serde(rename_all = "snake_case")]
+    pub enum ExecuteMsg {
+        Mint {
+            token_id: String,
+            owner: String,
+            token_uri: Option<String>,
+            extension: PositionMetadata,
+        },
+        Burn {
+            token_id: String,
+        },
+    }
+
+    #[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
+    #[serde(rename_all = "snake_case")]
+    pub enum QueryMsg {
+        OwnerOf {
+            token_id: String,
+            include_expired: Option<bool>,
+        },
+        Tokens {
+            owner: String,
+            start_after: Option<String>,
+            limit: Option<u32>,
+        },
+    }
+
+    /* What cw721 contracts send the receiver of a `SendNft` */
+    #[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
+    #[serde(rename_all = "snake_case")]
+    pub struct Cw721ReceiveMsg {
+        pub sender: String,
+        pub token_id: String,
+        pub msg: Binary,
+    }
+
+    #[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
+    #[serde(rename_all = "snake_case")]
+    pub struct OwnerOfResponse {
+        pub owner: String,
+    }
+
+    #[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
+    #[serde(rename_all = "snake_case")]
+    pub struct TokensResponse {
+        pub tokens: Vec<String>,
+    }
+}
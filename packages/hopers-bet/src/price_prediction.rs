@@ -4,8 +4,11 @@ use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 pub const FEE_PRECISION: u128 = 100u128;
+pub const BPS_PRECISION: u128 = 10_000u128;
+/* Strike markets are numbered from here so they never clash with rounds */
+pub const STRIKE_MARKET_ID_OFFSET: u128 = 1u128 << 64;
 
-#[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum Direction {
     Bull,
@@ -25,6 +28,90 @@ impl ToString for Direction {
     }
 }
 
+/**
+ * How a round is settled when nobody called the direction correctly
+ */
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TiePolicy {
+    /* Players get back the amount that entered the pool */
+    RefundNet,
+    /* Players also get back the gaming fee; burned tokens can't be returned */
+    RefundGross,
+    /* The whole pool is added to the accumulated fee */
+    HouseTakes,
+}
+
+impl Default for TiePolicy {
+    fn default() -> Self {
+        TiePolicy::RefundNet
+    }
+}
+
+/**
+ * Where the stakers' share of the market's own fees goes on distribution
+ */
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum FeeStaking {
+    /* Fee recipients get everything */
+    Disabled,
+    /* Sent to a staking contract along with `StakingHookMsg` */
+    Contract { contract_addr: Addr },
+    /* Held for stakers of a cw20 who stake it in this contract */
+    Internal { token_addr: Addr },
+}
+
+impl Default for FeeStaking {
+    fn default() -> Self {
+        FeeStaking::Disabled
+    }
+}
+
+/**
+ * When the gaming fee of a round is charged
+ */
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum FeeModel {
+    /* Burn and gaming fees come off every bet as it's placed */
+    OnStake,
+    /*
+     * Bets are free; the gaming fee is taken off the whole pool of rounds
+     * resolving with a winner, at the rate in force when the round opened
+     * for bets. Nothing is burned or paid to referrers, and fee tiers don't
+     * apply
+     */
+    OnWinnings,
+}
+
+impl Default for FeeModel {
+    fn default() -> Self {
+        FeeModel::OnStake
+    }
+}
+
+impl ToString for FeeModel {
+    fn to_string(&self) -> String {
+        match self {
+            FeeModel::OnStake => "on_stake",
+            FeeModel::OnWinnings => "on_winnings",
+        }
+        .to_string()
+    }
+}
+
+impl ToString for TiePolicy {
+    fn to_string(&self) -> String {
+        match self {
+            TiePolicy::RefundNet => "refund_net",
+            TiePolicy::RefundGross => "refund_gross",
+            TiePolicy::HouseTakes => "house_takes",
+        }
+        .to_string()
+    }
+}
+
 #[derive(Partial)]
 #[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
 #[serde(rename_all = "snake_case")]
@@ -39,7 +126,209 @@ pub struct Config {
     pub burn_fee: Uint128,
     pub gaming_fee: Uint128,
     pub token_addr: Addr,
+    #[serde(default)]
+    pub tie_policy: TiePolicy,
+    /* Closes within this many bps of the open price count as a tie */
+    #[serde(default)]
+    pub tie_band_bps: Uint128,
+    /* Share of a withdrawn bet kept as fee, same precision as the fees */
+    #[serde(default)]
+    pub withdrawal_penalty: Uint128,
+    /* Native or token factory denom staked instead of the cw20 token */
+    #[serde(default)]
+    pub stake_denom: Option<String>,
+    /* `SettleRound` pays winners out rather than crediting their balance */
+    #[serde(default)]
+    pub push_settlement: bool,
+    /* Companion cw721 contract minting a token for every new bet */
+    #[serde(default)]
+    pub position_nft_addr: Option<Addr>,
+    /*
+     * Ascending moves from the open price, in bps, splitting rounds into
+     * range buckets; bucket `i` starts at bound `i - 1`. Empty for bull/bear
+     */
+    #[serde(default)]
+    pub bucket_bounds_bps: Vec<i32>,
+    /* Gaming fee share paid to referrers, same precision as the fees */
+    #[serde(default)]
+    pub referral_share: Uint128,
+    #[serde(default)]
+    pub fee_staking: FeeStaking,
+    /* Share of the distributed fees that goes to stakers, same precision */
+    #[serde(default)]
+    pub staking_share: Uint128,
+    /* Seconds internal stakes stay locked after the staker last staked */
+    #[serde(default)]
+    pub staking_lock_seconds: Uint128,
+    /* Most the house pool seeds each side of a round with; zero disables it */
+    #[serde(default)]
+    pub house_seed_cap: Uint128,
+    /* Share of the distributed fees that goes to the house pool */
+    #[serde(default)]
+    pub house_fee_share: Uint128,
+    /* Applies to rounds opened for bets from then on */
+    #[serde(default)]
+    pub fee_model: FeeModel,
+    /*
+     * Caps on bets in the market's own stake, zero for no cap: the gross
+     * amount of a bet, the net position of a player in a round and the net
+     * amount all players bet in a round, house seeds excluded
+     */
+    #[serde(default)]
+    pub maximum_bet: Uint128,
+    #[serde(default)]
+    pub maximum_position: Uint128,
+    #[serde(default)]
+    pub maximum_round_pool: Uint128,
+}
+
+impl Config {
+    /**
+     * The token rounds are primarily staked in
+     */
+    pub fn stake_asset(&self) -> AssetInfo {
+        match &self.stake_denom {
+            Some(denom) => AssetInfo::NativeToken {
+                denom: denom.clone(),
+            },
+            None => AssetInfo::Token {
+                contract_addr: self.token_addr.clone(),
+            },
+        }
+    }
+
+    /**
+     * The market's own stake with its limits and fees
+     */
+    pub fn stake_token(&self) -> StakeToken {
+        StakeToken {
+            asset: self.stake_asset(),
+            minimum_bet: self.minimum_bet,
+            burn_fee: self.burn_fee,
+            gaming_fee: self.gaming_fee,
+            maximum_bet: self.maximum_bet,
+            maximum_position: self.maximum_position,
+            maximum_round_pool: self.maximum_round_pool,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AssetInfo {
+    Token { contract_addr: Addr },
+    NativeToken { denom: String },
+}
+
+impl AssetInfo {
+    /* Storage key of the asset: the cw20 address or the denom */
+    pub fn key(&self) -> String {
+        match self {
+            AssetInfo::Token { contract_addr } => contract_addr.to_string(),
+            AssetInfo::NativeToken { denom } => denom.clone(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct AssetAmount {
+    pub info: AssetInfo,
+    pub amount: Uint128,
+}
+
+/**
+ * A whitelisted token which can be bet besides the market's own stake
+ */
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct StakeToken {
+    pub asset: AssetInfo,
+    pub minimum_bet: Uint128,
+    pub burn_fee: Uint128,
+    pub gaming_fee: Uint128,
+    /* Same caps as the market's own stake has, in this token */
+    #[serde(default)]
+    pub maximum_bet: Uint128,
+    #[serde(default)]
+    pub maximum_position: Uint128,
+    #[serde(default)]
+    pub maximum_round_pool: Uint128,
+}
+
+/**
+ * What a player has to have bet or hold to get a fee tier
+ */
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum FeeTierRequirement {
+    /* Net stake bet in the market's stake over the trailing volume window */
+    Volume {
+        amount: Uint128,
+    },
+    Cw20Balance {
+        contract_addr: Addr,
+        amount: Uint128,
+    },
+    /* Counted up to 100 tokens */
+    Cw721Count {
+        contract_addr: Addr,
+        count: u32,
+    },
+}
+
+/**
+ * Discount on the burn and gaming fees; players get the best tier they meet
+ */
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct FeeTier {
+    pub name: String,
+    pub requirement: FeeTierRequirement,
+    /* Share of the fees waived, same precision as the fees */
+    pub discount: Uint128,
+}
+
+/**
+ * Bets of a round in one token; payouts never mix pools
+ */
+#[derive(
+    Serialize,
+    Deserialize,
+    Clone,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    JsonSchema
+)]
+#[serde(rename_all = "snake_case")]
+pub struct RoundPool {
+    pub bull_amount: Uint128,
+    pub bear_amount: Uint128,
+    pub fee_amount: Uint128,
+}
+
+impl RoundPool {
+    pub fn side_amount(&self, dir: &Direction) -> Uint128 {
+        match dir {
+            Direction::Bull => self.bull_amount,
+            Direction::Bear => self.bear_amount,
+        }
+    }
+
+    pub fn side_amount_mut(&mut self, dir: &Direction) -> &mut Uint128 {
+        match dir {
+            Direction::Bull => &mut self.bull_amount,
+            Direction::Bear => &mut self.bear_amount,
+        }
+    }
+
+    pub fn total(&self) -> Uint128 {
+        self.bull_amount + self.bear_amount
+    }
 }
+
 #[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub struct NextRound {
@@ -49,6 +338,34 @@ pub struct NextRound {
     pub close_time: Timestamp,
     pub bull_amount: Uint128,
     pub bear_amount: Uint128,
+    /* Gaming fee collected from the bets of this round */
+    #[serde(default)]
+    pub fee_amount: Uint128,
+    /* Players on each side, whatever token they bet in */
+    #[serde(default)]
+    pub bull_count: u64,
+    #[serde(default)]
+    pub bear_count: u64,
+    /* Put on each side by the house pool, included in the side amounts */
+    #[serde(default)]
+    pub house_seed: Uint128,
+    #[serde(default)]
+    pub fee_model: FeeModel,
+    /*
+     * With `FeeModel::OnWinnings`, the gaming fee rate the round charges;
+     * the current one for rounds opened before it was recorded
+     */
+    #[serde(default)]
+    pub winnings_fee_rate: Option<Uint128>,
+}
+
+impl NextRound {
+    pub fn side_count_mut(&mut self, dir: &Direction) -> &mut u64 {
+        match dir {
+            Direction::Bull => &mut self.bull_count,
+            Direction::Bear => &mut self.bear_count,
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
@@ -61,6 +378,18 @@ pub struct LiveRound {
     pub open_price: Uint128,
     pub bull_amount: Uint128,
     pub bear_amount: Uint128,
+    #[serde(default)]
+    pub fee_amount: Uint128,
+    #[serde(default)]
+    pub bull_count: u64,
+    #[serde(default)]
+    pub bear_count: u64,
+    #[serde(default)]
+    pub house_seed: Uint128,
+    #[serde(default)]
+    pub fee_model: FeeModel,
+    #[serde(default)]
+    pub winnings_fee_rate: Option<Uint128>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
@@ -75,10 +404,174 @@ pub struct FinishedRound {
     pub winner: Option<Direction>,
     pub bull_amount: Uint128,
     pub bear_amount: Uint128,
+    /* With `FeeModel::OnWinnings`, the fee taken off the pool at close */
+    #[serde(default)]
+    pub fee_amount: Uint128,
+    /* Policy in force when the round closed; applies if there's no winner */
+    #[serde(default)]
+    pub tie_policy: TiePolicy,
+    /* Bucket the price move fell in, for range rounds */
+    #[serde(default)]
+    pub winning_bucket: Option<u8>,
+    #[serde(default)]
+    pub bull_count: u64,
+    #[serde(default)]
+    pub bear_count: u64,
+    #[serde(default)]
+    pub house_seed: Uint128,
+    #[serde(default)]
+    pub fee_model: FeeModel,
+    #[serde(default)]
+    pub winnings_fee_rate: Option<Uint128>,
+}
+
+/**
+ * One-shot market on the price being above (bull) or below (bear) a strike
+ * at expiry; bets use `BetBull`/`BetBear` with the market id as round id
+ */
+#[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct StrikeMarket {
+    pub id: Uint128,
+    pub creator: Addr,
+    pub strike_price: Uint128,
+    pub bid_time: Timestamp,
+    /* Bets are taken until the cutoff */
+    pub cutoff_time: Timestamp,
+    /* Settled against the oracle price once expired */
+    pub expiry_time: Timestamp,
+    pub bull_amount: Uint128,
+    pub bear_amount: Uint128,
+    pub fee_amount: Uint128,
+    #[serde(default)]
+    pub bull_count: u64,
+    #[serde(default)]
+    pub bear_count: u64,
+    #[serde(default)]
+    pub fee_model: FeeModel,
+    #[serde(default)]
+    pub winnings_fee_rate: Option<Uint128>,
+}
+
+impl StrikeMarket {
+    /* The market seen as a round taking bets */
+    pub fn bidding_round(&self) -> NextRound {
+        NextRound {
+            id: self.id,
+            bid_time: self.bid_time,
+            open_time: self.cutoff_time,
+            close_time: self.expiry_time,
+            bull_amount: self.bull_amount,
+            bear_amount: self.bear_amount,
+            fee_amount: self.fee_amount,
+            bull_count: self.bull_count,
+            bear_count: self.bear_count,
+            house_seed: Uint128::zero(),
+            fee_model: self.fee_model.clone(),
+            winnings_fee_rate: self.winnings_fee_rate,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum OrderBy {
+    Asc,
+    Desc,
+}
+
+/**
+ * Outcomes of the rounds closed since the counters were introduced
+ */
+#[derive(
+    Serialize,
+    Deserialize,
+    Clone,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    JsonSchema
+)]
+#[serde(rename_all = "snake_case")]
+pub struct RoundStats {
+    pub bull_wins: u64,
+    pub bear_wins: u64,
+    pub ties: u64,
+    /* Range rounds are won by a bucket rather than a side */
+    pub range_rounds: u64,
+}
+
+/**
+ * Record of a player's settled bets in the market's stake; whitelisted
+ * token bets aren't counted. Bets count once `SettleRound`, a claim or their
+ * position token settles them
+ */
+#[derive(
+    Serialize,
+    Deserialize,
+    Clone,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    JsonSchema
+)]
+#[serde(rename_all = "snake_case")]
+pub struct PlayerStats {
+    pub rounds_played: u64,
+    pub wins: u64,
+    pub losses: u64,
+    pub ties: u64,
+    /* Net of fees */
+    pub total_staked: Uint128,
+    pub total_won: Uint128,
+    /* `total_won` less `total_staked` */
+    pub net_pnl: i128,
+    /* Consecutive wins; ties don't break a streak */
+    pub current_streak: u64,
+    pub best_streak: u64,
+}
+
+/**
+ * Lifetime totals of the market's stake; whitelisted tokens aren't counted
+ */
+#[derive(
+    Serialize,
+    Deserialize,
+    Clone,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    JsonSchema
+)]
+#[serde(rename_all = "snake_case")]
+pub struct GlobalStats {
+    /* Net stakes left in the rounds after withdrawals */
+    pub bull_volume: Uint128,
+    pub bear_volume: Uint128,
+    /* Top ups don't count as new bets */
+    pub bet_count: u64,
+    pub unique_players: u64,
+    pub total_burned: Uint128,
+    /* Gaming fees and penalties kept, less fees refunded on ties */
+    pub total_fees_accrued: Uint128,
+    pub total_fees_distributed: Uint128,
+    pub total_paid_out: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum LeaderboardOrder {
+    Pnl,
+    Volume,
 }
 
 pub mod msg {
     use super::*;
+    use crate::position_nft::msg::Cw721ReceiveMsg;
+    use cw20::Cw20ReceiveMsg;
 
     #[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
     #[serde(rename_all = "snake_case")]
@@ -98,11 +591,16 @@ pub mod msg {
         },
         /**
          * Price go up
+         * NOTE In native markets `amount` must be sent along as funds
          */
         BetBull {
             /* In case the TX is delayed */
             round_id: Uint128,
             amount: Uint128,
+            /* A whitelisted token; the market's stake if omitted */
+            token: Option<AssetInfo>,
+            /* Bound to the player on their first referred bet */
+            referrer: Option<String>,
         },
         /**
          * Price go down
@@ -111,6 +609,27 @@ pub mod msg {
             /* In case the TX is delayed */
             round_id: Uint128,
             amount: Uint128,
+            token: Option<AssetInfo>,
+            referrer: Option<String>,
+        },
+        /**
+         * Bet on the bucket the price move of a range round will fall in
+         */
+        BetBucket {
+            round_id: Uint128,
+            bucket: u8,
+            amount: Uint128,
+        },
+        /**
+         * Bet through a cw20 `Send` of the stake token, see `ReceiveMsg`
+         */
+        Receive(Cw20ReceiveMsg),
+        /**
+         * Reduce or, without an amount, withdraw a bet before the round locks
+         */
+        WithdrawBet {
+            round_id: Uint128,
+            amount: Option<Uint128>,
         },
         /**
          * Permissionless msg to close the current round and open the next
@@ -119,13 +638,162 @@ pub mod msg {
         CloseRound {},
         /**
          * Settle winnings for an account
+         * NOTE Only finished rounds are settled, at most `limit` bets per call
+         */
+        CollectWinnings {
+            /* Rounds to settle; the oldest finished ones if omitted */
+            round_ids: Option<Vec<Uint128>>,
+            limit: Option<u32>,
+            /* Claim for this player; the sender must be their operator */
+            player: Option<String>,
+            /* Pay out to this address; only the player can redirect */
+            recipient: Option<String>,
+        },
+        /**
+         * Pay out the referral rewards the sender earned so far; winnings
+         * are claimed with `CollectWinnings`
+         */
+        ClaimReferralRewards {},
+        /**
+         * Withdraw staked tokens of the internal staking once their lock-up
+         * is over, see `ReceiveMsg`
+         */
+        Unstake {
+            amount: Uint128,
+        },
+        ClaimStakingRewards {},
+        /**
+         * Deposit into the house pool with the attached coins of a native
+         * market; cw20 markets deposit through `ReceiveMsg`
+         * NOTE Shares are bought at the NAV when the live round next closes
+         */
+        DepositHouse {},
+        /**
+         * Queue house pool shares to be burnt for their NAV when the live
+         * round next closes, then claim them with `ClaimHouse`
+         * NOTE Waits for the seeds to come back if liquidity is short
          */
-        CollectWinnings {},
-        DistributeFund {
-            dev_wallet_list: Vec<WalletInfo>,
+        WithdrawHouse {
+            shares: Uint128,
+        },
+        /* Pay out the shares withdrawn at a past house epoch */
+        ClaimHouse {},
+        /**
+         * Settle the next `limit` bets of a finished round, recording their
+         * players' stats; winnings are paid out with `push_settlement` and
+         * credited to the players' claimable balance otherwise
+         * NOTE Permissionless; keepers run it on every finished round
+         */
+        SettleRound {
+            round_id: Uint128,
+            limit: Option<u32>,
+        },
+        /**
+         * Move the sender's bet in a round to another address
+         */
+        TransferBet {
+            round_id: Uint128,
+            recipient: String,
+        },
+        /**
+         * Settle a tokenized bet by a cw721 `SendNft` of its position token
+         * once the round finished; the market burns it and pays the sender
+         */
+        ReceiveNft(Cw721ReceiveMsg),
+        /**
+         * Open a strike market; admin or an allowed creator only
+         */
+        CreateStrikeMarket {
+            strike_price: Uint128,
+            cutoff_time: Timestamp,
+            expiry_time: Timestamp,
+        },
+        /**
+         * Settle an expired strike market; admin or an allowed creator only
+         * NOTE Markets settled over a minute after expiry are refunded, by
+         * anybody
+         */
+        SettleStrikeMarket {
+            market_id: Uint128,
+        },
+        SetStrikeCreator {
+            creator: String,
+            allowed: bool,
+        },
+        /**
+         * Let `operator` trigger the sender's claims, always paid to the sender
+         */
+        AllowClaimOperator {
+            operator: String,
+        },
+        RevokeClaimOperator {
+            operator: String,
+        },
+        /**
+         * Pay the undistributed fees out to the fee recipients; rounding
+         * dust is kept for the next distribution
+         */
+        DistributeFund {},
+        /**
+         * Replace the fee recipients; their ratios must sum to 1
+         */
+        SetFeeRecipients {
+            recipients: Vec<WalletInfo>,
         },
         Hault {},
         Resume {},
+        /**
+         * Whitelist a token for betting or update its minimum and fees
+         */
+        SetStakeToken {
+            token: StakeToken,
+        },
+        /**
+         * Stop accepting bets in a token; placed bets still pay out
+         */
+        RemoveStakeToken {
+            asset: AssetInfo,
+        },
+        /**
+         * Replace the fee tiers; an empty list charges everyone the full fees
+         */
+        SetFeeTiers {
+            tiers: Vec<FeeTier>,
+        },
+    }
+
+    /**
+     * Embedded in the `msg` of a cw20 `Send`; the sent amount is the bet
+     */
+    #[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
+    #[serde(rename_all = "snake_case")]
+    pub enum ReceiveMsg {
+        BetBull {
+            round_id: Uint128,
+            referrer: Option<String>,
+        },
+        BetBear {
+            round_id: Uint128,
+            referrer: Option<String>,
+        },
+        BetBucket {
+            round_id: Uint128,
+            bucket: u8,
+        },
+        /* Stake with `FeeStaking::Internal`, sent from its token */
+        Stake {},
+        /* Deposit into the house pool, sent from the market's token */
+        DepositHouse {},
+    }
+
+    /**
+     * Executed on the staking contract of `FeeStaking::Contract`; for cw20
+     * stakes it's the `msg` of a cw20 `Send`, native stakes are attached
+     */
+    #[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
+    #[serde(rename_all = "snake_case")]
+    pub enum StakingHookMsg {
+        DistributeRewards {},
     }
 
     #[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
@@ -139,14 +807,134 @@ pub mod msg {
         FinishedRound {
             round_id: Uint128,
         },
+        /**
+         * Finished rolling rounds; strike markets are listed separately
+         */
+        FinishedRounds {
+            start_after: Option<Uint128>,
+            limit: Option<u32>,
+            order: Option<OrderBy>,
+        },
+        /**
+         * The finished round that was live at `timestamp`, if any
+         */
+        RoundAtTime {
+            timestamp: Timestamp,
+        },
+        RoundStats {},
+        GlobalStats {},
+        PlayerStats {
+            player: String,
+        },
+        /**
+         * Players by descending PnL or volume; `start_after` is the last
+         * player of the previous page. Covers the bets settled so far
+         */
+        Leaderboard {
+            order_by: LeaderboardOrder,
+            start_after: Option<String>,
+            limit: Option<u32>,
+        },
         MyGameList {
             player: Addr,
             start_after: Option<Uint128>,
             limit: Option<u32>,
         },
+        /**
+         * Bets placed in a round, by player address, paid out ones included
+         */
+        RoundBets {
+            round_id: Uint128,
+            direction: Option<Direction>,
+            start_after: Option<String>,
+            limit: Option<u32>,
+        },
+        /**
+         * Winnings of the player's settled bets not claimed yet; bets count
+         * once `SettleRound`, a claim or a new bet settles them
+         */
         MyPendingReward {
             player: Addr,
         },
+        StakeTokens {
+            start_after: Option<String>,
+            limit: Option<u32>,
+        },
+        FeeTiers {},
+        FeeRecipients {},
+        StakingState {},
+        Staker {
+            address: String,
+        },
+        /**
+         * The house pool and its net asset value
+         */
+        HousePool {},
+        HouseShares {
+            address: String,
+        },
+        /**
+         * Past fee distributions, oldest first
+         */
+        FeeDistributions {
+            start_after: Option<u64>,
+            limit: Option<u32>,
+        },
+        /**
+         * The tier the player's next bet gets
+         */
+        PlayerFeeTier {
+            player: String,
+        },
+        ClaimOperators {
+            player: String,
+        },
+        /**
+         * Lifetime and unclaimed referral rewards of a referrer, per token
+         */
+        ReferralEarnings {
+            referrer: String,
+        },
+        ReferredPlayers {
+            referrer: String,
+            start_after: Option<String>,
+            limit: Option<u32>,
+        },
+        /**
+         * Current payout multipliers of a round's own stake pool
+         */
+        Odds {
+            round_id: Uint128,
+        },
+        /**
+         * Fees and odds of a bet without placing it
+         */
+        SimulateBet {
+            round_id: Uint128,
+            direction: Direction,
+            gross: Uint128,
+            /* Checked for a conflicting bet if given */
+            player: Option<String>,
+        },
+        StrikeMarket {
+            market_id: Uint128,
+        },
+        StrikeMarkets {
+            start_after: Option<Uint128>,
+            limit: Option<u32>,
+        },
+        /**
+         * Bounds and pools of a range round
+         */
+        RoundBuckets {
+            round_id: Uint128,
+        },
+        /**
+         * Pools of the whitelisted tokens bet in a round
+         */
+        RoundPools {
+            round_id: Uint128,
+        },
     }
 }
 
@@ -172,6 +960,167 @@ pub mod response {
         pub next_bear_amount: Uint128,
         pub next_bull_amount: Uint128,
     }
+
+    #[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
+    #[serde(rename_all = "snake_case")]
+    pub struct FeeTiersResponse {
+        pub tiers: Vec<FeeTier>,
+    }
+
+    #[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
+    #[serde(rename_all = "snake_case")]
+    pub struct StakerResponse {
+        pub amount: Uint128,
+        pub pending_reward: Uint128,
+        /* The stake can't be unstaked before then */
+        pub locked_until: Timestamp,
+    }
+
+    #[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
+    #[serde(rename_all = "snake_case")]
+    pub struct HousePoolResponse {
+        pub pool: HousePool,
+        pub nav: Uint128,
+        /* Value of one share; one until the first deposit */
+        pub share_price: Decimal,
+    }
+
+    #[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
+    #[serde(rename_all = "snake_case")]
+    pub struct HouseSharesResponse {
+        pub shares: Uint128,
+        pub value: Uint128,
+        /* Queued for the current epoch */
+        pub pending_deposit: Uint128,
+        pub pending_shares: Uint128,
+        /* Withdrawn at a past epoch, see `ExecuteMsg::ClaimHouse` */
+        pub claimable: Uint128,
+    }
+
+    #[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
+    #[serde(rename_all = "snake_case")]
+    pub struct FeeRecipientsResponse {
+        pub recipients: Vec<WalletInfo>,
+    }
+
+    #[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
+    #[serde(rename_all = "snake_case")]
+    pub struct FeeDistributionsResponse {
+        pub distributions: Vec<FeeDistribution>,
+    }
+
+    #[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
+    #[serde(rename_all = "snake_case")]
+    pub struct PlayerFeeTierResponse {
+        pub tier: Option<FeeTier>,
+        pub trailing_volume: Uint128,
+    }
+
+    #[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
+    #[serde(rename_all = "snake_case")]
+    pub struct ClaimOperatorsResponse {
+        pub operators: Vec<Addr>,
+    }
+
+    #[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
+    #[serde(rename_all = "snake_case")]
+    pub struct ReferralEarningsResponse {
+        pub earnings: Vec<AssetAmount>,
+        /* Not claimed yet with `ClaimReferralRewards` */
+        pub claimable: Vec<AssetAmount>,
+    }
+
+    #[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
+    #[serde(rename_all = "snake_case")]
+    pub struct ReferredPlayersResponse {
+        pub players: Vec<Addr>,
+    }
+
+    #[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
+    #[serde(rename_all = "snake_case")]
+    pub struct StakeTokensResponse {
+        pub tokens: Vec<StakeToken>,
+    }
+
+    #[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
+    #[serde(rename_all = "snake_case")]
+    pub struct FinishedRoundsResponse {
+        pub rounds: Vec<FinishedRound>,
+    }
+
+    #[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
+    #[serde(rename_all = "snake_case")]
+    pub struct RoundAtTimeResponse {
+        pub round: Option<FinishedRound>,
+    }
+
+    pub type RoundStatsResponse = RoundStats;
+
+    pub type GlobalStatsResponse = GlobalStats;
+
+    pub type PlayerStatsResponse = PlayerStats;
+
+    #[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
+    #[serde(rename_all = "snake_case")]
+    pub struct LeaderboardEntry {
+        pub player: Addr,
+        pub stats: PlayerStats,
+    }
+
+    #[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
+    #[serde(rename_all = "snake_case")]
+    pub struct LeaderboardResponse {
+        pub entries: Vec<LeaderboardEntry>,
+    }
+
+    /**
+     * Multipliers are what the pool pays per unit of net stake; `None`
+     * while nobody bet the side
+     */
+    #[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
+    #[serde(rename_all = "snake_case")]
+    pub struct OddsResponse {
+        pub bull_multiplier: Option<Decimal>,
+        pub bear_multiplier: Option<Decimal>,
+    }
+
+    #[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
+    #[serde(rename_all = "snake_case")]
+    pub struct SimulateBetResponse {
+        pub burn_fee: Uint128,
+        pub gaming_fee: Uint128,
+        /* What enters the pool */
+        pub net_stake: Uint128,
+        /* Multipliers once the bet is in */
+        pub bull_multiplier: Option<Decimal>,
+        pub bear_multiplier: Option<Decimal>,
+        pub payout_if_win: Uint128,
+        /* Why the bet would be rejected */
+        pub error: Option<String>,
+    }
+
+    pub type StrikeMarketResponse = StrikeMarket;
+
+    #[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
+    #[serde(rename_all = "snake_case")]
+    pub struct StrikeMarketsResponse {
+        pub markets: Vec<StrikeMarket>,
+    }
+
+    #[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
+    #[serde(rename_all = "snake_case")]
+    pub struct RoundBucketsResponse {
+        pub bounds_bps: Vec<i32>,
+        /* Net amount bet on every bucket, by index */
+        pub pools: Vec<Uint128>,
+    }
+
+    #[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
+    #[serde(rename_all = "snake_case")]
+    pub struct RoundPoolsResponse {
+        /* Keyed by cw20 address or denom */
+        pub pools: Vec<(String, RoundPool)>,
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
@@ -179,3 +1128,83 @@ pub struct WalletInfo {
     pub address: Addr,
     pub ratio: Decimal,
 }
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct FeeDistribution {
+    pub id: u64,
+    pub time: Timestamp,
+    /* Amount paid out of every token, dust excluded */
+    pub amounts: Vec<AssetAmount>,
+    pub recipients: Vec<WalletInfo>,
+    /* Stakers' share of the market's own fees */
+    #[serde(default)]
+    pub staking_amount: Uint128,
+    /* House pool's share of the market's own fees */
+    #[serde(default)]
+    pub house_amount: Uint128,
+}
+
+/**
+ * Reward-per-share accounting of `FeeStaking::Internal`
+ */
+#[derive(
+    Serialize,
+    Deserialize,
+    Clone,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    JsonSchema
+)]
+#[serde(rename_all = "snake_case")]
+pub struct StakingState {
+    pub total_staked: Uint128,
+    /* Rewards per staked token since staking began */
+    pub reward_per_share: Decimal,
+    /* Rounding dust of `reward_per_share`, added to the next distribution */
+    #[serde(default)]
+    pub undistributed: Uint128,
+}
+
+/**
+ * Liquidity backing one-sided rounds, owned by the holders of its shares
+ */
+#[derive(
+    Serialize,
+    Deserialize,
+    Clone,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    JsonSchema
+)]
+#[serde(rename_all = "snake_case")]
+pub struct HousePool {
+    /* Held by the contract, free to seed or withdraw */
+    pub liquidity: Uint128,
+    /* Seeded in rounds that haven't closed yet */
+    pub seeded: Uint128,
+    pub total_shares: Uint128,
+    /* Deposits queued for the current epoch, not part of the NAV yet */
+    #[serde(default)]
+    pub pending_deposits: Uint128,
+    /* Shares queued to be withdrawn, still counted in `total_shares` */
+    #[serde(default)]
+    pub pending_shares: Uint128,
+    /* Withdrawn at a past epoch but not claimed yet */
+    #[serde(default)]
+    pub unclaimed: Uint128,
+    /* Settles when a live round closes, with only unopened rounds seeded */
+    #[serde(default)]
+    pub epoch: u64,
+}
+
+impl HousePool {
+    /* Net asset value, seeds counted at cost */
+    pub fn nav(&self) -> Uint128 {
+        self.liquidity + self.seeded
+    }
+}